@@ -0,0 +1,117 @@
+// -------------------------------------------------------------------------------------------------
+//
+//  Implementation of a general purpose logger.
+//
+//  Copyright (c) 2025 by Dr. Panos Asproulis (p.asproulis@icloud.com).
+//  All Rights Reserved.
+//
+// -------------------------------------------------------------------------------------------------
+
+//! Throughput benchmarks of the logger.
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use logger::{LogLevel, Logger};
+use std::hint::black_box;
+
+///
+/// The number of threads used by the multi-threaded benchmarks.
+///
+const THREADS: usize = 4;
+
+///
+/// The number of messages logged by each thread per iteration.
+///
+const MESSAGES_PER_THREAD: usize = 16;
+
+///
+/// Create a logger writing to a file in the temporary directory.
+///
+/// - Arguments:
+///   - `name`: The name of the benchmark, used for the file name.
+///
+/// - Returns:
+///   - The logger and the path of its log file.
+///
+fn bench_logger(name: &str) -> (Logger, std::path::PathBuf) {
+    let path = std::env::temp_dir().join(format!("logger_bench_{}.log", name));
+    let logger = Logger::new(path.to_str().unwrap(), false);
+    (logger, path)
+}
+
+///
+/// The messages logged by the benchmarks: a small and a large one.
+///
+fn messages() -> Vec<(&'static str, String)> {
+    vec![
+        ("small", "Benchmark message".to_string()),
+        ("large", "x".repeat(4_096)),
+    ]
+}
+
+///
+/// Benchmark logging from a single thread.
+///
+fn single_threaded(c: &mut Criterion) {
+    let mut group = c.benchmark_group("single_threaded");
+
+    for (name, message) in messages() {
+        let (logger, path) = bench_logger(&format!("single_{}", name));
+        group.throughput(Throughput::Bytes(message.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &message, |b, message| {
+            b.iter(|| logger.log(LogLevel::Info, black_box(message)).unwrap())
+        });
+        let _ = std::fs::remove_file(path);
+    }
+
+    group.finish();
+}
+
+///
+/// Benchmark logging through the single-threaded `LocalLogger`.
+///
+fn local(c: &mut Criterion) {
+    let mut group = c.benchmark_group("local");
+
+    for (name, message) in messages() {
+        let path = std::env::temp_dir().join(format!("logger_bench_local_{}.log", name));
+        let logger = Logger::single_threaded(path.to_str().unwrap(), false).unwrap();
+        group.throughput(Throughput::Bytes(message.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &message, |b, message| {
+            b.iter(|| logger.log(LogLevel::Info, black_box(message)).unwrap())
+        });
+        let _ = std::fs::remove_file(path);
+    }
+
+    group.finish();
+}
+
+///
+/// Benchmark logging from several threads sharing the same logger.
+///
+fn multi_threaded(c: &mut Criterion) {
+    let mut group = c.benchmark_group("multi_threaded");
+
+    for (name, message) in messages() {
+        let (logger, path) = bench_logger(&format!("multi_{}", name));
+        group.throughput(Throughput::Elements((THREADS * MESSAGES_PER_THREAD) as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &message, |b, message| {
+            b.iter(|| {
+                std::thread::scope(|scope| {
+                    for _ in 0..THREADS {
+                        scope.spawn(|| {
+                            for _ in 0..MESSAGES_PER_THREAD {
+                                logger.log(LogLevel::Info, black_box(message)).unwrap();
+                            }
+                        });
+                    }
+                })
+            })
+        });
+        let _ = std::fs::remove_file(path);
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, single_threaded, local, multi_threaded);
+criterion_main!(benches);
@@ -0,0 +1,569 @@
+// -------------------------------------------------------------------------------------------------
+//
+//  Implementation of a general purpose logger.
+//
+//  Copyright (c) 2025 by Dr. Panos Asproulis (p.asproulis@icloud.com).
+//  All Rights Reserved.
+//
+// -------------------------------------------------------------------------------------------------
+
+//! Implementation of the asynchronous logger, which hands the records to a
+//! background thread through a bounded channel, so that the callers do
+//! not wait for the log destination.
+
+use std::collections::VecDeque;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::errors::LoggerError;
+use crate::levels::LogLevel;
+use crate::logger::Logger;
+
+///
+/// The behaviour of an `AsyncLogger` when a record is logged while its
+/// channel is full. The dropping modes count the dropped records.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Backpressure {
+    ///
+    /// Block the caller until the background thread makes room, so that
+    /// no record is lost.
+    ///
+    #[default]
+    Block,
+    ///
+    /// Discard the record being logged, keeping the queued ones.
+    ///
+    DropNewest,
+    ///
+    /// Discard the oldest queued record to make room for the record being
+    /// logged.
+    ///
+    DropOldest,
+}
+
+///
+/// The state of the channel, protected by its mutex.
+///
+struct State {
+    ///
+    /// The queued records, oldest first.
+    ///
+    records: VecDeque<(LogLevel, String)>,
+    ///
+    /// If true then no more records are accepted and the background thread
+    /// exits once the queue is empty. The channel is also closed when the
+    /// background thread exits, so that no caller waits for it.
+    ///
+    closed: bool,
+}
+
+///
+/// The bounded channel shared by an `AsyncLogger` and its background
+/// thread.
+///
+struct Channel {
+    ///
+    /// The state of the channel.
+    ///
+    state: Mutex<State>,
+    ///
+    /// Signalled when a record is queued or the channel is closed.
+    ///
+    not_empty: Condvar,
+    ///
+    /// Signalled when a record is taken from the queue.
+    ///
+    not_full: Condvar,
+    ///
+    /// The maximum number of queued records.
+    ///
+    capacity: usize,
+    ///
+    /// The behaviour when the queue is full.
+    ///
+    backpressure: Backpressure,
+    ///
+    /// The number of records dropped because the queue was full.
+    ///
+    dropped: AtomicU64,
+}
+
+//
+// Implementation of the `Channel` struct.
+//
+impl Channel {
+    ///
+    /// Lock the state of the channel. A state poisoned by a panic in
+    /// another thread is still consistent, since it is only modified by
+    /// single queue operations.
+    ///
+    /// - Returns:
+    ///   - The guard of the locked state.
+    ///
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    ///
+    /// Close the channel, waking up the background thread and the blocked
+    /// callers.
+    ///
+    fn close(&self) {
+        self.lock().closed = true;
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+    }
+}
+
+///
+/// A guard closing the channel when the background thread exits, even
+/// by a panic.
+///
+struct CloseOnExit<'a>(&'a Channel);
+
+//
+// Implementation of the `Drop` trait for `CloseOnExit`.
+//
+impl Drop for CloseOnExit<'_> {
+    fn drop(&mut self) {
+        self.0.close();
+    }
+}
+
+///
+/// A logger which writes its records from a background thread. The
+/// records are queued in a bounded channel, and the behaviour when the
+/// channel is full is selected by its `Backpressure` mode. Dropping the
+/// `AsyncLogger` writes all the queued records and waits for the
+/// background thread to exit; `AsyncLogger::shutdown` does the same within
+/// a timeout.
+///
+pub struct AsyncLogger {
+    ///
+    /// The channel shared with the background thread.
+    ///
+    channel: Arc<Channel>,
+    ///
+    /// A clone of the logger which writes the records, shut down by
+    /// `AsyncLogger::shutdown`.
+    ///
+    logger: Logger,
+    ///
+    /// The background thread writing the records.
+    ///
+    worker: Option<JoinHandle<()>>,
+}
+
+//
+// Implementation of the `AsyncLogger` struct.
+//
+impl AsyncLogger {
+    ///
+    /// Create a new `AsyncLogger`, starting its background thread.
+    ///
+    /// - Arguments:
+    ///   - `logger`: The logger which writes the records.
+    ///   - `capacity`: The maximum number of queued records, at least 1.
+    ///   - `backpressure`: The behaviour when the channel is full.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `AsyncLogger` object.
+    ///
+    pub fn new(logger: Logger, capacity: usize, backpressure: Backpressure) -> Self {
+        let capacity = capacity.max(1);
+        let channel = Arc::new(Channel {
+            state: Mutex::new(State {
+                records: VecDeque::with_capacity(capacity),
+                closed: false,
+            }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            capacity,
+            backpressure,
+            dropped: AtomicU64::new(0),
+        });
+        let worker_channel = Arc::clone(&channel);
+        let worker_logger = logger.clone();
+        let worker = std::thread::spawn(move || run_worker(&worker_channel, &worker_logger));
+
+        Self {
+            channel,
+            logger,
+            worker: Some(worker),
+        }
+    }
+
+    ///
+    /// Queue a message for logging. If the channel is full, the caller is
+    /// blocked or a record is dropped according to the backpressure mode.
+    /// The message is discarded if the channel is closed, e.g. because the
+    /// background thread has exited.
+    ///
+    /// - Arguments:
+    ///   - `level`: The level of the message.
+    ///   - `message`: The message to log.
+    ///
+    pub fn log(&self, level: LogLevel, message: &str) {
+        let channel = &self.channel;
+        let mut state = channel.lock();
+
+        if state.closed {
+            return;
+        }
+
+        if state.records.len() >= channel.capacity {
+            match channel.backpressure {
+                Backpressure::Block => {
+                    state = channel
+                        .not_full
+                        .wait_while(state, |state| {
+                            state.records.len() >= channel.capacity && !state.closed
+                        })
+                        .unwrap_or_else(PoisonError::into_inner);
+                }
+                Backpressure::DropNewest => {
+                    channel.dropped.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+                Backpressure::DropOldest => {
+                    state.records.pop_front();
+                    channel.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+
+        if state.closed {
+            return;
+        }
+
+        state.records.push_back((level, message.to_string()));
+        channel.not_empty.notify_one();
+    }
+
+    ///
+    /// Get the number of records dropped because the channel was full.
+    ///
+    /// - Returns:
+    ///   - The number of dropped records.
+    ///
+    pub fn dropped(&self) -> u64 {
+        self.channel.dropped.load(Ordering::Relaxed)
+    }
+
+    ///
+    /// Get the number of records queued and not yet taken by the
+    /// background thread.
+    ///
+    /// - Returns:
+    ///   - The number of queued records.
+    ///
+    pub fn queued(&self) -> usize {
+        self.channel.lock().records.len()
+    }
+
+    ///
+    /// Shut the asynchronous logger down: the channel is closed, the queued
+    /// records are written by the background thread, and the logger is
+    /// shut down with `Logger::shutdown`, all within the timeout.
+    ///
+    /// - Arguments:
+    ///   - `timeout`: The maximum time to wait for the draining.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure. If the draining did not
+    ///     complete in time, an I/O error of kind `TimedOut` is returned
+    ///     and the draining continues in the background.
+    ///
+    pub fn shutdown(mut self, timeout: Duration) -> Result<(), LoggerError> {
+        let deadline = Instant::now() + timeout;
+        self.channel.close();
+        //
+        // The background thread is detached if it does not exit in time.
+        //
+        if let Some(worker) = self.worker.take() {
+            while !worker.is_finished() {
+                if Instant::now() >= deadline {
+                    return Err(LoggerError::Io(std::io::Error::new(
+                        std::io::ErrorKind::TimedOut,
+                        "Logger: the queued records were not drained in time.",
+                    )));
+                }
+
+                std::thread::sleep(Duration::from_millis(1));
+            }
+
+            let _ = worker.join();
+        }
+
+        self.logger
+            .shutdown(deadline.saturating_duration_since(Instant::now()))
+    }
+}
+
+//
+// Implementation of the `std::fmt::Debug` trait for `AsyncLogger`.
+//
+impl std::fmt::Debug for AsyncLogger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncLogger")
+            .field("backpressure", &self.channel.backpressure)
+            .field("queued", &self.queued())
+            .field("dropped", &self.dropped())
+            .finish()
+    }
+}
+
+//
+// Implementation of the `Drop` trait for `AsyncLogger`.
+//
+impl Drop for AsyncLogger {
+    ///
+    /// Close the channel and wait for the background thread to write the
+    /// queued records.
+    ///
+    fn drop(&mut self) {
+        self.channel.close();
+
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+///
+/// Run the background thread of an `AsyncLogger`, writing the queued
+/// records until the channel is closed and empty.
+///
+/// - Arguments:
+///   - `channel`: The channel of the records.
+///   - `logger`: The logger which writes the records.
+///
+fn run_worker(channel: &Channel, logger: &Logger) {
+    let _close_on_exit = CloseOnExit(channel);
+
+    loop {
+        let (level, message) = {
+            let mut state = channel
+                .not_empty
+                .wait_while(channel.lock(), |state| {
+                    state.records.is_empty() && !state.closed
+                })
+                .unwrap_or_else(PoisonError::into_inner);
+
+            match state.records.pop_front() {
+                Some(record) => record,
+                None => return,
+            }
+        };
+
+        channel.not_full.notify_one();
+        //
+        // There is no caller to report the error to, so it is shown on the
+        // standard error. A panic, e.g. by `WriteFailurePolicy::Panic`, only
+        // loses its record, so that the thread keeps serving the callers.
+        //
+        match std::panic::catch_unwind(AssertUnwindSafe(|| logger.log(level, &message))) {
+            Ok(Ok(())) => (),
+            Ok(Err(error)) => eprintln!("Logger: {}", error),
+            Err(_) => eprintln!("Logger: the record could not be written."),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//
+// Unit Tests.
+//
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::prelude::*;
+
+    //
+    // A writer which records the written lines and blocks until its gate is
+    // opened, holding the background thread so that the channel fills up.
+    //
+    #[derive(Clone, Default)]
+    struct GatedWriter {
+        gate: Arc<(Mutex<bool>, Condvar)>,
+        lines: Arc<Mutex<Vec<u8>>>,
+    }
+
+    impl GatedWriter {
+        fn open(&self) {
+            *self.gate.0.lock().unwrap() = true;
+            self.gate.1.notify_all();
+        }
+
+        fn contents(&self) -> String {
+            String::from_utf8(self.lines.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl Write for GatedWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let (open, opened) = &*self.gate;
+            drop(
+                opened
+                    .wait_while(open.lock().unwrap(), |open| !*open)
+                    .unwrap(),
+            );
+            self.lines.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    //
+    // Create an `AsyncLogger` with a capacity of two records, whose
+    // background thread is held writing the record `0`.
+    //
+    fn filled_logger(backpressure: Backpressure) -> (AsyncLogger, GatedWriter) {
+        let writer = GatedWriter::default();
+        let mut logger = Logger::from_writer(writer.clone(), false);
+        logger.set_console_writer(std::io::sink());
+
+        let async_logger = AsyncLogger::new(logger, 2, backpressure);
+        async_logger.log(LogLevel::Info, "0");
+
+        while async_logger.queued() > 0 {
+            std::thread::yield_now();
+        }
+
+        async_logger.log(LogLevel::Info, "1");
+        async_logger.log(LogLevel::Info, "2");
+        (async_logger, writer)
+    }
+
+    #[test]
+    fn test_async_logger_drop_newest() {
+        let (async_logger, writer) = filled_logger(Backpressure::DropNewest);
+
+        async_logger.log(LogLevel::Info, "3");
+        assert_eq!(async_logger.dropped(), 1);
+
+        writer.open();
+        drop(async_logger);
+        assert_eq!(writer.contents(), "[INFO] 0\n[INFO] 1\n[INFO] 2\n");
+    }
+
+    #[test]
+    fn test_async_logger_drop_oldest() {
+        let (async_logger, writer) = filled_logger(Backpressure::DropOldest);
+
+        async_logger.log(LogLevel::Info, "3");
+        assert_eq!(async_logger.dropped(), 1);
+
+        writer.open();
+        drop(async_logger);
+        assert_eq!(writer.contents(), "[INFO] 0\n[INFO] 2\n[INFO] 3\n");
+    }
+
+    #[test]
+    fn test_async_logger_block() {
+        let (async_logger, writer) = filled_logger(Backpressure::Block);
+
+        std::thread::scope(|scope| {
+            let caller = scope.spawn(|| async_logger.log(LogLevel::Info, "3"));
+
+            std::thread::sleep(Duration::from_millis(50));
+            assert!(!caller.is_finished());
+
+            writer.open();
+            caller.join().unwrap();
+        });
+        assert_eq!(async_logger.dropped(), 0);
+
+        drop(async_logger);
+        assert_eq!(
+            writer.contents(),
+            "[INFO] 0\n[INFO] 1\n[INFO] 2\n[INFO] 3\n"
+        );
+    }
+
+    //
+    // A writer which always fails, like a file on a full disk.
+    //
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::StorageFull.into())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_async_logger_block_with_failing_writer() {
+        //
+        // The default write failure policy panics on every record, which
+        // must neither stop the background thread nor block the callers.
+        //
+        let mut logger = Logger::from_writer(FailingWriter, false);
+        logger.set_console_writer(std::io::sink());
+
+        let async_logger = AsyncLogger::new(logger, 1, Backpressure::Block);
+
+        std::thread::scope(|scope| {
+            let caller = scope.spawn(|| {
+                for index in 0..10 {
+                    async_logger.log(LogLevel::Info, &index.to_string());
+                }
+            });
+
+            let deadline = Instant::now() + Duration::from_secs(10);
+
+            while !caller.is_finished() {
+                assert!(Instant::now() < deadline, "the caller is blocked");
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        });
+
+        assert!(async_logger.shutdown(Duration::from_secs(10)).is_ok());
+    }
+
+    #[test]
+    fn test_async_logger_debug() {
+        let (async_logger, writer) = filled_logger(Backpressure::DropNewest);
+
+        async_logger.log(LogLevel::Info, "3");
+        assert_eq!(
+            format!("{:?}", async_logger),
+            "AsyncLogger { backpressure: DropNewest, queued: 2, dropped: 1 }"
+        );
+        writer.open();
+    }
+
+    #[test]
+    fn test_async_logger_shutdown() {
+        let (async_logger, writer) = filled_logger(Backpressure::Block);
+
+        writer.open();
+        async_logger.shutdown(Duration::from_secs(10)).unwrap();
+        assert_eq!(writer.contents(), "[INFO] 0\n[INFO] 1\n[INFO] 2\n");
+    }
+
+    #[test]
+    fn test_async_logger_shutdown_timeout() {
+        let (async_logger, writer) = filled_logger(Backpressure::Block);
+
+        match async_logger.shutdown(Duration::from_millis(50)) {
+            Err(LoggerError::Io(error)) => {
+                assert_eq!(error.kind(), std::io::ErrorKind::TimedOut)
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+        writer.open();
+    }
+}
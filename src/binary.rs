@@ -0,0 +1,206 @@
+// -------------------------------------------------------------------------------------------------
+//
+//  Implementation of a general purpose logger.
+//
+//  Copyright (c) 2025 by Dr. Panos Asproulis (p.asproulis@icloud.com).
+//  All Rights Reserved.
+//
+// -------------------------------------------------------------------------------------------------
+
+//! Implementation of the binary log format.
+//!
+//! Each record consists of a 13-byte header, holding the severity of the
+//! level in one byte, the timestamp in nanoseconds since the Unix epoch as
+//! a little-endian `u64` and the length of the message in bytes as a
+//! little-endian `u32`, followed by the UTF-8 bytes of the message.
+
+use std::fs::File;
+use std::io::{BufReader, ErrorKind, Read};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use crate::levels::LogLevel;
+
+///
+/// The size of the header of a binary record in bytes.
+///
+const HEADER_SIZE: usize = 13;
+
+///
+/// A record decoded from a binary log file.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    ///
+    /// The level of the record.
+    ///
+    pub level: LogLevel,
+    ///
+    /// The time at which the record was logged.
+    ///
+    pub time: SystemTime,
+    ///
+    /// The message of the record, including its fields.
+    ///
+    pub message: String,
+}
+
+///
+/// Encode a record in the binary log format.
+///
+/// - Arguments:
+///   - `level`: The level of the record.
+///   - `time`: The time of the record.
+///   - `message`: The message of the record.
+///
+/// - Returns:
+///   - The bytes of the encoded record.
+///
+pub(crate) fn encode_record(level: LogLevel, time: SystemTime, message: &str) -> Vec<u8> {
+    let nanos = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + message.len());
+
+    bytes.push(level.severity());
+    bytes.extend_from_slice(&nanos.to_le_bytes());
+    bytes.extend_from_slice(&(message.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(message.as_bytes());
+
+    bytes
+}
+
+///
+/// An iterator over the records of a binary log file.
+///
+#[derive(Debug)]
+pub struct BinaryLogReader<R: Read> {
+    ///
+    /// The reader of the binary log.
+    ///
+    reader: R,
+}
+
+//
+// Implementation of the `BinaryLogReader` struct.
+//
+impl<R: Read> BinaryLogReader<R> {
+    ///
+    /// Create a new `BinaryLogReader`.
+    ///
+    /// - Arguments:
+    ///   - `reader`: The reader of the binary log.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `BinaryLogReader` object.
+    ///
+    pub fn new(reader: R) -> Self {
+        Self { reader }
+    }
+
+    ///
+    /// Decode the next record.
+    ///
+    /// - Returns:
+    ///   - The next record, `None` at the end of the log, or the error
+    ///     which occurred.
+    ///
+    fn read_record(&mut self) -> std::io::Result<Option<LogRecord>> {
+        let mut header = [0_u8; HEADER_SIZE];
+        //
+        // A clean end of the log may only occur between records.
+        //
+        let mut read = 0;
+
+        while read < HEADER_SIZE {
+            match self.reader.read(&mut header[read..])? {
+                0 if read == 0 => return Ok(None),
+                0 => return Err(ErrorKind::UnexpectedEof.into()),
+                count => read += count,
+            }
+        }
+
+        let level = *LogLevel::all()
+            .get(header[0] as usize)
+            .ok_or_else(|| std::io::Error::new(ErrorKind::InvalidData, "invalid level"))?;
+        let nanos = u64::from_le_bytes(header[1..9].try_into().unwrap_or_default());
+        let length = u32::from_le_bytes(header[9..13].try_into().unwrap_or_default());
+
+        let mut message = vec![0_u8; length as usize];
+        self.reader.read_exact(&mut message)?;
+        let message = String::from_utf8(message)
+            .map_err(|error| std::io::Error::new(ErrorKind::InvalidData, error))?;
+
+        Ok(Some(LogRecord {
+            level,
+            time: SystemTime::UNIX_EPOCH + Duration::from_nanos(nanos),
+            message,
+        }))
+    }
+}
+
+//
+// Implementation of the `Iterator` trait for `BinaryLogReader`.
+//
+impl<R: Read> Iterator for BinaryLogReader<R> {
+    type Item = std::io::Result<LogRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+///
+/// Open a log file written in the binary format for reading.
+///
+/// - Arguments:
+///   - `path`: The path of the binary log file.
+///
+/// - Returns:
+///   - An iterator over the records of the file, or the error which
+///     occurred while opening it.
+///
+pub fn read_binary_log(
+    path: impl AsRef<Path>,
+) -> std::io::Result<BinaryLogReader<BufReader<File>>> {
+    Ok(BinaryLogReader::new(BufReader::new(File::open(path)?)))
+}
+
+// -------------------------------------------------------------------------------------------------
+//
+// Unit Tests.
+//
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binary_round_trip() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_nanos(1_735_826_399_123_456_789);
+        let mut bytes = encode_record(LogLevel::Warning, time, "Disk almost full");
+        bytes.extend(encode_record(LogLevel::Debug, time, ""));
+
+        let records: Vec<LogRecord> = BinaryLogReader::new(bytes.as_slice())
+            .collect::<std::io::Result<_>>()
+            .unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].level, LogLevel::Warning);
+        assert_eq!(records[0].time, time);
+        assert_eq!(records[0].message, "Disk almost full");
+        assert_eq!(records[1].level, LogLevel::Debug);
+        assert_eq!(records[1].message, "");
+    }
+
+    #[test]
+    fn test_binary_truncated_record() {
+        let bytes = encode_record(LogLevel::Info, SystemTime::UNIX_EPOCH, "Hello");
+        let mut reader = BinaryLogReader::new(&bytes[..bytes.len() - 1]);
+
+        let error = reader.next().unwrap().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    }
+}
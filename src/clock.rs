@@ -0,0 +1,118 @@
+// -------------------------------------------------------------------------------------------------
+//
+//  Implementation of a general purpose logger.
+//
+//  Copyright (c) 2025 by Dr. Panos Asproulis (p.asproulis@icloud.com).
+//  All Rights Reserved.
+//
+// -------------------------------------------------------------------------------------------------
+
+//! Implementation of the clocks used for timestamping the logged messages.
+
+use std::time::SystemTime;
+
+///
+/// A source of the current time.
+///
+pub trait Clock: std::fmt::Debug + Send + Sync {
+    ///
+    /// Get the current time.
+    ///
+    /// - Returns:
+    ///   - The current time according to this clock.
+    ///
+    fn now(&self) -> SystemTime;
+}
+
+///
+/// A clock which returns the real system time.
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+//
+// Implementation of the `Clock` trait for `SystemClock`.
+//
+impl Clock for SystemClock {
+    ///
+    /// Get the current system time.
+    ///
+    /// - Returns:
+    ///   - The current system time.
+    ///
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+///
+/// A clock which always returns the same time. Useful for producing
+/// deterministic timestamps in tests.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedClock {
+    ///
+    /// The time returned by the clock.
+    ///
+    time: SystemTime,
+}
+
+//
+// Implementation of the `FixedClock` struct.
+//
+impl FixedClock {
+    ///
+    /// Create a new `FixedClock`.
+    ///
+    /// - Arguments:
+    ///   - `time`: The time the clock will always return.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `FixedClock` object.
+    ///
+    pub fn new(time: SystemTime) -> Self {
+        Self { time }
+    }
+}
+
+//
+// Implementation of the `Clock` trait for `FixedClock`.
+//
+impl Clock for FixedClock {
+    ///
+    /// Get the fixed time of the clock.
+    ///
+    /// - Returns:
+    ///   - The fixed time.
+    ///
+    fn now(&self) -> SystemTime {
+        self.time
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//
+// Unit Tests.
+//
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_fixed_clock() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let clock = FixedClock::new(time);
+        assert_eq!(clock.now(), time);
+        assert_eq!(clock.now(), time);
+    }
+
+    #[test]
+    fn test_system_clock() {
+        let before = SystemTime::now();
+        let now = SystemClock.now();
+        assert!(now >= before);
+    }
+}
@@ -0,0 +1,493 @@
+// -------------------------------------------------------------------------------------------------
+//
+//  Implementation of a general purpose logger.
+//
+//  Copyright (c) 2025 by Dr. Panos Asproulis (p.asproulis@icloud.com).
+//  All Rights Reserved.
+//
+// -------------------------------------------------------------------------------------------------
+
+//! Implementation of the logger configuration.
+//!
+//! With the `serde` feature enabled, a `LoggerConfig` can be loaded from a
+//! TOML or JSON file.
+
+use std::collections::HashMap;
+
+use crate::errors::LoggerError;
+use crate::flush::FlushPolicy;
+use crate::format::{ControlChars, LevelLabel, LineEnding, LineTermination, LogFormat};
+use crate::levels::LogLevel;
+use crate::logger::Logger;
+use crate::rotation::RotationPeriod;
+use crate::timestamp::TimestampFormat;
+
+///
+/// The configuration of a `Logger`.
+///
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct LoggerConfig {
+    ///
+    /// The complete path of the file to use for logging.
+    ///
+    pub path: String,
+    ///
+    /// If true then terminate the application when an error message is logged.
+    ///
+    pub terminate_on_error: bool,
+    ///
+    /// The exit code of the process when it is terminated because of a
+    /// logged error.
+    ///
+    pub error_exit_code: i32,
+    ///
+    /// If true then the missing parent directories of the log file are
+    /// created.
+    ///
+    pub create_dirs: bool,
+    ///
+    /// If true then the log file is only created on the first write.
+    ///
+    pub lazy: bool,
+    ///
+    /// The least severe level of the messages which are logged.
+    ///
+    pub level: LogLevel,
+    ///
+    /// The format of the logged lines.
+    ///
+    pub format: LogFormat,
+    ///
+    /// The format of the console lines, if different from `format`.
+    ///
+    pub console_format: Option<LogFormat>,
+    ///
+    /// The template of the plain lines, if different from the fixed layout.
+    ///
+    pub format_template: Option<String>,
+    ///
+    /// The label used for rendering the level of each logged line.
+    ///
+    pub level_label: LevelLabel,
+    ///
+    /// The labels overriding the level label for specific levels, e.g.
+    /// `ERREUR` for `Error`.
+    ///
+    pub level_labels: HashMap<LogLevel, String>,
+    ///
+    /// The format of the timestamp prefixed to each logged message.
+    ///
+    pub timestamp_format: TimestampFormat,
+    ///
+    /// The line ending appended to each line written to the log file.
+    ///
+    pub line_ending: LineEnding,
+    ///
+    /// The placement of the line endings between the records.
+    ///
+    pub line_termination: LineTermination,
+    ///
+    /// The handling of the control characters contained in each message.
+    ///
+    pub control_chars: ControlChars,
+    ///
+    /// If true then the process id is included in each logged line.
+    ///
+    pub include_pid: bool,
+    ///
+    /// If true then the hostname is included in each logged line.
+    ///
+    pub include_hostname: bool,
+    ///
+    /// If true then the flush policy is `FlushPolicy::OnError`, whatever
+    /// `flush_policy` says. It is kept for the existing configurations.
+    ///
+    pub durable: bool,
+    ///
+    /// The policy deciding when the buffered records are written to the
+    /// log destination.
+    ///
+    pub flush_policy: FlushPolicy,
+    ///
+    /// The maximum number of characters of a message, if limited.
+    ///
+    pub max_message_len: Option<usize>,
+    ///
+    /// If true then a UTF-8 byte order mark is written at the start of
+    /// each new log file.
+    ///
+    pub write_bom: bool,
+    ///
+    /// The size in bytes above which the log file is rotated, if limited.
+    ///
+    pub max_file_size: Option<u64>,
+    ///
+    /// The maximum number of rotated log files to keep.
+    ///
+    pub max_backups: usize,
+    ///
+    /// The period after which the logging switches to a new file, if any.
+    ///
+    pub rotation_period: Option<RotationPeriod>,
+}
+
+//
+// Implementation of the `Default` trait for `LoggerConfig`.
+//
+impl Default for LoggerConfig {
+    ///
+    /// Create a default `LoggerConfig`.
+    ///
+    /// - Returns:
+    ///   - The default `LoggerConfig` object.
+    ///
+    fn default() -> Self {
+        Self {
+            path: "default.log".to_string(),
+            terminate_on_error: false,
+            error_exit_code: 1,
+            create_dirs: false,
+            lazy: false,
+            level: LogLevel::Debug,
+            format: LogFormat::Plain,
+            console_format: None,
+            format_template: None,
+            level_label: LevelLabel::Full,
+            level_labels: HashMap::new(),
+            timestamp_format: TimestampFormat::None,
+            line_ending: LineEnding::Lf,
+            line_termination: LineTermination::Terminated,
+            control_chars: ControlChars::Verbatim,
+            include_pid: false,
+            include_hostname: false,
+            durable: false,
+            flush_policy: FlushPolicy::EveryLine,
+            max_message_len: None,
+            write_bom: false,
+            max_file_size: None,
+            max_backups: 5,
+            rotation_period: None,
+        }
+    }
+}
+
+//
+// Implementation of the `LoggerConfig` parsers.
+//
+#[cfg(feature = "serde")]
+impl LoggerConfig {
+    ///
+    /// Parse a `LoggerConfig` from a TOML string. Unknown fields are
+    /// rejected.
+    ///
+    /// - Arguments:
+    ///   - `toml`: The TOML configuration.
+    ///
+    /// - Returns:
+    ///   - The parsed `LoggerConfig` or `LoggerError::InvalidConfig`.
+    ///
+    pub fn from_toml_str(toml: &str) -> Result<Self, LoggerError> {
+        toml::from_str(toml).map_err(|error| LoggerError::InvalidConfig(error.to_string()))
+    }
+
+    ///
+    /// Parse a `LoggerConfig` from a JSON string. Unknown fields are
+    /// rejected.
+    ///
+    /// - Arguments:
+    ///   - `json`: The JSON configuration.
+    ///
+    /// - Returns:
+    ///   - The parsed `LoggerConfig` or `LoggerError::InvalidConfig`.
+    ///
+    pub fn from_json_str(json: &str) -> Result<Self, LoggerError> {
+        serde_json::from_str(json).map_err(|error| LoggerError::InvalidConfig(error.to_string()))
+    }
+}
+
+//
+// Implementation of the `Logger` constructors from a configuration.
+//
+impl Logger {
+    ///
+    /// Create a new `Logger` from a configuration.
+    ///
+    /// - Arguments:
+    ///   - `config`: The configuration of the `Logger`.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `Logger` object or the error which occurred.
+    ///
+    pub fn from_config(config: &LoggerConfig) -> Result<Self, LoggerError> {
+        let mut logger = Logger::open_configured(
+            &config.path,
+            config.lazy,
+            config.create_dirs,
+            config.terminate_on_error,
+        )?;
+
+        logger.set_error_exit_code(config.error_exit_code);
+        logger.set_min_level(config.level);
+        logger.set_format(config.format);
+        logger.set_console_format(config.console_format);
+        logger.set_format_template(config.format_template.as_deref())?;
+        logger.set_level_label(config.level_label);
+        logger.set_level_labels(config.level_labels.clone());
+        logger.set_timestamp_format(config.timestamp_format);
+        logger.set_line_ending(config.line_ending);
+        logger.set_line_termination(config.line_termination);
+        logger.set_control_chars(config.control_chars);
+        logger.set_include_pid(config.include_pid);
+        logger.set_include_hostname(config.include_hostname);
+        logger.set_flush_policy(config.flush_policy);
+
+        if config.durable {
+            logger.set_durable(true);
+        }
+
+        logger.set_max_message_len(config.max_message_len);
+        logger.set_write_bom(config.write_bom)?;
+        logger.set_max_file_size(config.max_file_size);
+        logger.set_max_backups(config.max_backups);
+        logger.set_rotation_period(config.rotation_period);
+
+        Ok(logger)
+    }
+
+    ///
+    /// Create a new `Logger` from a configuration file. Files with a
+    /// `.json` extension are parsed as JSON and all others as TOML.
+    ///
+    /// - Arguments:
+    ///   - `path`: The path of the configuration file.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `Logger` object or the error which occurred.
+    ///
+    #[cfg(feature = "serde")]
+    pub fn from_config_file(path: impl AsRef<std::path::Path>) -> Result<Self, LoggerError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+
+        let config = if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            LoggerConfig::from_json_str(&contents)?
+        } else {
+            LoggerConfig::from_toml_str(&contents)?
+        };
+
+        Logger::from_config(&config)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//
+// Unit Tests.
+//
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+    use std::fs;
+    use std::path::Path;
+
+    //
+    // Helper function to generate unique log filenames for tests
+    //
+    fn get_unique_log_filename() -> String {
+        let mut rng = rand::rng();
+        format!("test_log_{}.log", rng.random::<u64>())
+    }
+
+    #[test]
+    fn test_logger_from_config() {
+        let log_file = get_unique_log_filename();
+        let config = LoggerConfig {
+            path: log_file.clone(),
+            level: LogLevel::Warning,
+            ..LoggerConfig::default()
+        };
+        let logger = Logger::from_config(&config).unwrap();
+
+        logger.log(LogLevel::Info, "Dropped").unwrap();
+        logger.log(LogLevel::Warning, "Kept").unwrap();
+
+        assert_eq!(fs::read_to_string(&log_file).unwrap(), "[WARNING] Kept\n");
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_config_round_trip() {
+        let log_file = get_unique_log_filename();
+        let config = LoggerConfig {
+            path: log_file.clone(),
+            error_exit_code: 3,
+            level: LogLevel::Info,
+            format: LogFormat::Json,
+            console_format: Some(LogFormat::Compact),
+            timestamp_format: TimestampFormat::Rfc3339Millis,
+            line_ending: LineEnding::CrLf,
+            include_pid: true,
+            max_message_len: Some(200),
+            max_file_size: Some(1_048_576),
+            max_backups: 3,
+            rotation_period: Some(RotationPeriod::Daily),
+            flush_policy: FlushPolicy::EveryN(10),
+            create_dirs: true,
+            lazy: true,
+            ..LoggerConfig::default()
+        };
+        let logger = Logger::from_config(&config).unwrap();
+
+        assert_eq!(logger.config(), config);
+        //
+        // The durable flag is reported as its flush policy.
+        //
+        let durable = Logger::from_config(&LoggerConfig {
+            durable: true,
+            ..config.clone()
+        })
+        .unwrap();
+        assert_eq!(durable.config().flush_policy, FlushPolicy::OnError);
+        assert!(!durable.config().durable);
+        //
+        // The lazy logger has not created its log file.
+        //
+        assert!(!Path::new(&log_file).exists());
+    }
+
+    #[test]
+    fn test_logger_from_config_invalid_template() {
+        let log_file = get_unique_log_filename();
+        let config = LoggerConfig {
+            path: log_file.clone(),
+            format_template: Some("[{level}] {mesage}".to_string()),
+            ..LoggerConfig::default()
+        };
+
+        match Logger::from_config(&config) {
+            Err(LoggerError::InvalidTemplate(unknown)) => assert_eq!(unknown, "{mesage}"),
+            result => panic!("unexpected result: {:?}", result.map(|_| ())),
+        }
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_from_config_create_dirs() {
+        let root = std::env::temp_dir().join(get_unique_log_filename());
+        let log_file = root.join("sub").join("sub2").join("x.log");
+        let mut config = LoggerConfig {
+            path: log_file.to_string_lossy().into_owned(),
+            ..LoggerConfig::default()
+        };
+
+        assert!(Logger::from_config(&config).is_err());
+
+        config.create_dirs = true;
+        let logger = Logger::from_config(&config).unwrap();
+        logger.log(LogLevel::Info, "Created").unwrap();
+
+        assert!(root.join("sub").join("sub2").is_dir());
+        assert_eq!(fs::read_to_string(&log_file).unwrap(), "[INFO] Created\n");
+        //
+        // Clean up.
+        //
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_logger_level_labels_from_config() {
+        let log_file = get_unique_log_filename();
+        let toml = format!(
+            "path = \"{}\"\n\n[level_labels]\nerror = \"ERREUR\"\n",
+            log_file
+        );
+        let config = LoggerConfig::from_toml_str(&toml).unwrap();
+        assert_eq!(config.level_labels[&LogLevel::Error], "ERREUR");
+
+        let logger = Logger::from_config(&config).unwrap();
+        logger.log(LogLevel::Error, "Échec").unwrap();
+        logger.log(LogLevel::Info, "Démarré").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "[ERREUR] Échec\n[INFO] Démarré\n"
+        );
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_logger_from_toml_config() {
+        let log_file = get_unique_log_filename();
+        let toml = format!(
+            "path = \"{}\"\nlevel = \"warning\"\ntimestamp_format = \"uptime_seconds\"\n",
+            log_file
+        );
+        let config = LoggerConfig::from_toml_str(&toml).unwrap();
+        assert_eq!(config.level, LogLevel::Warning);
+        assert_eq!(config.timestamp_format, TimestampFormat::UptimeSeconds);
+
+        let logger = Logger::from_config(&config).unwrap();
+        logger.log(LogLevel::Info, "Dropped").unwrap();
+        logger.log(LogLevel::Warning, "Kept").unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        assert!(contents.starts_with("[+"));
+        assert!(contents.ends_with("s] [WARNING] Kept\n"));
+        assert_eq!(contents.lines().count(), 1);
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_logger_from_config_file() {
+        let log_file = get_unique_log_filename();
+        let config_file = format!("{}.json", log_file);
+        fs::write(
+            &config_file,
+            format!("{{\"path\": \"{}\", \"level\": \"ERROR\"}}", log_file),
+        )
+        .unwrap();
+
+        let logger = Logger::from_config_file(&config_file).unwrap();
+        logger.log(LogLevel::Warning, "Dropped").unwrap();
+        logger.log(LogLevel::Error, "Kept").unwrap();
+
+        assert_eq!(fs::read_to_string(&log_file).unwrap(), "[ERROR] Kept\n");
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+        fs::remove_file(&config_file).unwrap();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_config_unknown_field() {
+        let result = LoggerConfig::from_toml_str("colour = true\n");
+        assert!(matches!(result, Err(LoggerError::InvalidConfig(_))));
+    }
+}
@@ -0,0 +1,316 @@
+// -------------------------------------------------------------------------------------------------
+//
+//  Implementation of a general purpose logger.
+//
+//  Copyright (c) 2025 by Dr. Panos Asproulis (p.asproulis@icloud.com).
+//  All Rights Reserved.
+//
+// -------------------------------------------------------------------------------------------------
+
+//! Implementation of the destination of the logged lines.
+
+use std::fs::{File, OpenOptions};
+use std::io::prelude::*;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+///
+/// The target to which the logged lines are written.
+///
+pub(crate) enum Target {
+    ///
+    /// A log file opened in append mode.
+    ///
+    File(File),
+    ///
+    /// An arbitrary writer.
+    ///
+    Writer(Box<dyn Write + Send>),
+    ///
+    /// A log file which is created on the first write.
+    ///
+    Lazy(PathBuf),
+    ///
+    /// A spool directory in which each write creates a new file.
+    ///
+    Spool(Spool),
+}
+
+///
+/// A spool directory, in which each record is written to its own file for
+/// a downstream process to consume and delete. The files are named after a
+/// zero-padded sequence number, e.g. `000001.log`, and existing files are
+/// never overwritten: their numbers are skipped instead.
+///
+pub(crate) struct Spool {
+    ///
+    /// The path of the spool directory.
+    ///
+    directory: PathBuf,
+    ///
+    /// The sequence number of the next file.
+    ///
+    next: u64,
+}
+
+//
+// Implementation of the `Spool` struct.
+//
+impl Spool {
+    ///
+    /// Create a new `Spool`, creating its directory if it does not exist.
+    ///
+    /// - Arguments:
+    ///   - `directory`: The path of the spool directory.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `Spool` object or the error which occurred.
+    ///
+    pub(crate) fn new(directory: &Path) -> std::io::Result<Self> {
+        std::fs::create_dir_all(directory)?;
+
+        Ok(Self {
+            directory: directory.to_path_buf(),
+            next: 1,
+        })
+    }
+
+    ///
+    /// Write a record to a new file of the spool directory.
+    ///
+    /// - Arguments:
+    ///   - `record`: The bytes of the record, including its terminator.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    fn write_record(&mut self, record: &[u8]) -> std::io::Result<()> {
+        loop {
+            let path = self.directory.join(format!("{:06}.log", self.next));
+            self.next += 1;
+
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => return file.write_all(record),
+                Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+///
+/// Create a log file, truncating it if it exists, and open it in append
+/// mode.
+///
+/// - Arguments:
+///   - `path`: The path of the log file.
+///
+/// - Returns:
+///   - The open log file or the error which occurred.
+///
+pub(crate) fn create_log_file(path: &Path) -> std::io::Result<File> {
+    File::create(path)?;
+    OpenOptions::new().append(true).open(path)
+}
+
+//
+// Implementation of the `Target` enum.
+//
+impl Target {
+    ///
+    /// Synchronise the target to the disk. For a writer this only flushes it.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    fn sync(&mut self) -> std::io::Result<()> {
+        match self {
+            Target::File(file) => file.sync_all(),
+            Target::Writer(writer) => writer.flush(),
+            Target::Lazy(_) | Target::Spool(_) => Ok(()),
+        }
+    }
+}
+
+//
+// Implementation of the `Write` trait for `Target`.
+//
+impl Write for Target {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Target::File(file) => file.write(buf),
+            Target::Writer(writer) => writer.write(buf),
+            Target::Lazy(path) => {
+                *self = Target::File(create_log_file(path)?);
+                self.write(buf)
+            }
+            Target::Spool(spool) => {
+                spool.write_record(buf)?;
+                Ok(buf.len())
+            }
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Target::File(file) => file.flush(),
+            Target::Writer(writer) => writer.flush(),
+            Target::Lazy(_) | Target::Spool(_) => Ok(()),
+        }
+    }
+}
+
+///
+/// The destination of the logged lines, kept open between calls to `log`.
+/// Lines may be held in a buffer and written to the target in batches.
+///
+pub(crate) struct Destination {
+    ///
+    /// The target to which the lines are written.
+    ///
+    pub(crate) target: Target,
+    ///
+    /// The bytes of the buffered lines which are not yet written.
+    ///
+    pending: Vec<u8>,
+    ///
+    /// The number of buffered lines which are not yet written.
+    ///
+    pending_records: usize,
+    ///
+    /// The time at which the oldest buffered line was buffered, if any.
+    ///
+    pending_since: Option<Instant>,
+    ///
+    /// The label of the rotation period of the open log file, if the log
+    /// file is rotated by period.
+    ///
+    pub(crate) period: Option<String>,
+    ///
+    /// If true then the target ends with a record which is not followed
+    /// by a line ending, so the next record must be preceded by one.
+    ///
+    pub(crate) needs_separator: bool,
+}
+
+//
+// Implementation of the `Destination` struct.
+//
+impl Destination {
+    ///
+    /// Create a new `Destination`.
+    ///
+    /// - Arguments:
+    ///   - `target`: The target to which the lines are written.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `Destination` object.
+    ///
+    pub(crate) fn new(target: Target) -> Self {
+        Self {
+            target,
+            pending: Vec::new(),
+            pending_records: 0,
+            pending_since: None,
+            period: None,
+            needs_separator: false,
+        }
+    }
+
+    ///
+    /// Add a complete line to the buffer without writing it.
+    ///
+    /// - Arguments:
+    ///   - `line`: The bytes of the line, including its terminator.
+    ///
+    pub(crate) fn buffer_line(&mut self, line: &[u8]) {
+        self.pending.extend_from_slice(line);
+        self.pending_records += 1;
+        self.pending_since.get_or_insert_with(Instant::now);
+    }
+
+    ///
+    /// Get the number of buffered lines which are not yet written.
+    ///
+    /// - Returns:
+    ///   - The number of buffered lines.
+    ///
+    pub(crate) fn pending_records(&self) -> usize {
+        self.pending_records
+    }
+
+    ///
+    /// Get the time for which the oldest buffered line has been buffered.
+    ///
+    /// - Returns:
+    ///   - The age of the oldest buffered line, or zero if none is buffered.
+    ///
+    pub(crate) fn pending_age(&self) -> Duration {
+        self.pending_since
+            .map_or(Duration::ZERO, |pending_since| pending_since.elapsed())
+    }
+
+    ///
+    /// Write complete lines with a single `write` call. Since log files
+    /// are opened in append mode, POSIX guarantees that the lines are
+    /// appended atomically, so lines written concurrently by several
+    /// processes never interleave. Should the call write only part of the
+    /// lines, the remainder is written in further calls.
+    ///
+    /// - Arguments:
+    ///   - `lines`: The bytes of the lines, including their terminators.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    pub(crate) fn write_line(&mut self, lines: &[u8]) -> std::io::Result<()> {
+        let written = self.target.write(lines)?;
+
+        if written < lines.len() {
+            self.target.write_all(&lines[written..])?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Write the buffered lines to the target and flush it.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    pub(crate) fn flush(&mut self) -> std::io::Result<()> {
+        if !self.pending.is_empty() {
+            let pending = std::mem::take(&mut self.pending);
+            self.pending_records = 0;
+            self.pending_since = None;
+            self.write_line(&pending)?;
+        }
+
+        self.target.flush()
+    }
+
+    ///
+    /// Write the buffered lines and synchronise the target to the disk.
+    /// For a writer this only flushes it.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    pub(crate) fn sync(&mut self) -> std::io::Result<()> {
+        self.flush()?;
+        self.target.sync()
+    }
+}
+
+//
+// Implementation of the `Drop` trait for `Destination`.
+//
+impl Drop for Destination {
+    ///
+    /// Write the buffered lines on a best-effort basis.
+    ///
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
@@ -0,0 +1,186 @@
+// -------------------------------------------------------------------------------------------------
+//
+//  Implementation of a general purpose logger.
+//
+//  Copyright (c) 2025 by Dr. Panos Asproulis (p.asproulis@icloud.com).
+//  All Rights Reserved.
+//
+// -------------------------------------------------------------------------------------------------
+
+//! Implementation of the logger errors.
+
+///
+/// The errors which may be produced by the logger.
+///
+#[derive(Debug)]
+pub enum LoggerError {
+    ///
+    /// An I/O operation on the log destination failed.
+    ///
+    Io(std::io::Error),
+    ///
+    /// A format template contains invalid placeholders.
+    ///
+    InvalidTemplate(String),
+    ///
+    /// A string does not name a valid logging level.
+    ///
+    InvalidLevel(String),
+    ///
+    /// A logger configuration cannot be parsed.
+    ///
+    InvalidConfig(String),
+    ///
+    /// The path of the log file is an existing directory.
+    ///
+    PathIsDirectory(String),
+}
+
+//
+// Implementation of the `std::fmt::Display` trait for `LoggerError`.
+//
+impl std::fmt::Display for LoggerError {
+    ///
+    /// Format the `LoggerError` as a string.
+    ///
+    /// - Arguments:
+    ///  - `f`: The formatter to use for formatting.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoggerError::Io(error) => write!(f, "I/O error: {}", error),
+            LoggerError::InvalidTemplate(template) => {
+                write!(f, "invalid format template: {}", template)
+            }
+            LoggerError::InvalidLevel(level) => write!(f, "invalid log level: {}", level),
+            LoggerError::InvalidConfig(reason) => {
+                write!(f, "invalid logger configuration: {}", reason)
+            }
+            LoggerError::PathIsDirectory(path) => {
+                write!(f, "the log file path is a directory: {}", path)
+            }
+        }
+    }
+}
+
+//
+// Implementation of the `std::error::Error` trait for `LoggerError`.
+//
+impl std::error::Error for LoggerError {
+    ///
+    /// Get the underlying cause of the error.
+    ///
+    /// - Returns:
+    ///   - The underlying error, if any.
+    ///
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoggerError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+//
+// Implementation of the `From<std::io::Error>` trait for `LoggerError`.
+//
+impl From<std::io::Error> for LoggerError {
+    ///
+    /// Convert an I/O error to a `LoggerError`.
+    ///
+    /// - Arguments:
+    ///   - `error`: The I/O error to convert.
+    ///
+    /// - Returns:
+    ///   - The corresponding `LoggerError`.
+    ///
+    fn from(error: std::io::Error) -> Self {
+        LoggerError::Io(error)
+    }
+}
+
+///
+/// The behaviour of the logger when a record cannot be written to the log
+/// destination, e.g. because the disk is full.
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum WriteFailurePolicy {
+    ///
+    /// Panic, which crashes the application.
+    ///
+    #[default]
+    Panic,
+    ///
+    /// Discard the record and count it in the dropped records.
+    ///
+    Drop,
+    ///
+    /// Switch the log destination to an alternate file, e.g. under `/tmp`,
+    /// and write the record there. If the alternate file cannot be written
+    /// either, then panic.
+    ///
+    Fallback(std::path::PathBuf),
+}
+
+// -------------------------------------------------------------------------------------------------
+//
+// Unit Tests.
+//
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::LoggerError;
+    use std::error::Error;
+
+    #[test]
+    fn test_io_error_display() {
+        let error = LoggerError::Io(std::io::Error::other("disk full"));
+        assert_eq!(error.to_string(), "I/O error: disk full");
+        assert!(error.source().is_some());
+    }
+
+    #[test]
+    fn test_invalid_template_display() {
+        let error = LoggerError::InvalidTemplate("{mesage}".to_string());
+        assert_eq!(error.to_string(), "invalid format template: {mesage}");
+        assert!(error.source().is_none());
+    }
+
+    #[test]
+    fn test_invalid_level_display() {
+        let error = LoggerError::InvalidLevel("LOUD".to_string());
+        assert_eq!(error.to_string(), "invalid log level: LOUD");
+        assert!(error.source().is_none());
+    }
+
+    #[test]
+    fn test_invalid_config_display() {
+        let error = LoggerError::InvalidConfig("unknown field `colour`".to_string());
+        assert_eq!(
+            error.to_string(),
+            "invalid logger configuration: unknown field `colour`"
+        );
+        assert!(error.source().is_none());
+    }
+
+    #[test]
+    fn test_path_is_directory_display() {
+        let error = LoggerError::PathIsDirectory("logs".to_string());
+        assert_eq!(error.to_string(), "the log file path is a directory: logs");
+        assert!(error.source().is_none());
+    }
+
+    #[test]
+    fn test_from_io_error() {
+        fn fails() -> Result<(), LoggerError> {
+            Err(std::io::Error::new(std::io::ErrorKind::NotFound, "missing"))?;
+            Ok(())
+        }
+
+        assert!(matches!(fails(), Err(LoggerError::Io(_))));
+    }
+}
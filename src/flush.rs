@@ -0,0 +1,142 @@
+// -------------------------------------------------------------------------------------------------
+//
+//  Implementation of a general purpose logger.
+//
+//  Copyright (c) 2025 by Dr. Panos Asproulis (p.asproulis@icloud.com).
+//  All Rights Reserved.
+//
+// -------------------------------------------------------------------------------------------------
+
+//! Implementation of the policies deciding when the logged records are
+//! written to the log destination.
+
+use std::time::Duration;
+
+use crate::levels::LogLevel;
+
+///
+/// The policy deciding when the buffered records are written to the log
+/// destination. Whatever the policy, error messages are always written
+/// immediately, together with any buffered records preceding them, and
+/// the buffered records are also written by `Logger::flush`,
+/// `Logger::sync` and when the last clone of the `Logger` is dropped.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum FlushPolicy {
+    ///
+    /// Write every record immediately.
+    ///
+    #[default]
+    EveryLine,
+    ///
+    /// Hold the records in memory until they are flushed explicitly or an
+    /// error message is logged.
+    ///
+    Buffered,
+    ///
+    /// Hold the records in memory and write them once the oldest of them
+    /// has been buffered for the interval. The interval is checked when a
+    /// record is logged, so a quiet logger holds its records until the
+    /// next record or an explicit flush.
+    ///
+    Interval(Duration),
+    ///
+    /// Hold the records in memory and write them in a single batch once
+    /// the specified number of records has accumulated.
+    ///
+    EveryN(usize),
+    ///
+    /// Write every record immediately, and synchronise the log file to the
+    /// disk with `File::sync_all` after every error message, so that the
+    /// message survives a power loss. This is considerably slower than a
+    /// plain flush, which only hands the data to the operating system.
+    ///
+    OnError,
+}
+
+//
+// Implementation of the `FlushPolicy` enum.
+//
+impl FlushPolicy {
+    ///
+    /// Decide whether the buffered records are written after a record is
+    /// buffered.
+    ///
+    /// - Arguments:
+    ///   - `level`: The level of the buffered record.
+    ///   - `pending_records`: The number of buffered records.
+    ///   - `pending_age`: The time for which the oldest buffered record
+    ///     has been buffered.
+    ///
+    /// - Returns:
+    ///   - True if the buffered records must be written.
+    ///
+    pub(crate) fn is_due(
+        &self,
+        level: LogLevel,
+        pending_records: usize,
+        pending_age: Duration,
+    ) -> bool {
+        if level == LogLevel::Error {
+            return true;
+        }
+
+        match self {
+            FlushPolicy::EveryLine | FlushPolicy::OnError => true,
+            FlushPolicy::Buffered => false,
+            FlushPolicy::Interval(interval) => pending_age >= *interval,
+            FlushPolicy::EveryN(records) => pending_records >= *records,
+        }
+    }
+
+    ///
+    /// Decide whether the log file is synchronised to the disk after a
+    /// record is written.
+    ///
+    /// - Arguments:
+    ///   - `level`: The level of the written record.
+    ///
+    /// - Returns:
+    ///   - True if the log file must be synchronised.
+    ///
+    pub(crate) fn syncs(&self, level: LogLevel) -> bool {
+        *self == FlushPolicy::OnError && level == LogLevel::Error
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//
+// Unit Tests.
+//
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flush_policy_is_due() {
+        let second = Duration::from_secs(1);
+
+        assert!(FlushPolicy::EveryLine.is_due(LogLevel::Debug, 1, Duration::ZERO));
+        assert!(!FlushPolicy::Buffered.is_due(LogLevel::Warning, 1000, second));
+        assert!(FlushPolicy::Buffered.is_due(LogLevel::Error, 1, Duration::ZERO));
+        assert!(!FlushPolicy::Interval(second).is_due(LogLevel::Info, 5, Duration::ZERO));
+        assert!(FlushPolicy::Interval(second).is_due(LogLevel::Info, 5, second));
+        assert!(!FlushPolicy::EveryN(3).is_due(LogLevel::Info, 2, second));
+        assert!(FlushPolicy::EveryN(3).is_due(LogLevel::Info, 3, Duration::ZERO));
+        assert!(FlushPolicy::OnError.is_due(LogLevel::Info, 1, Duration::ZERO));
+    }
+
+    #[test]
+    fn test_flush_policy_syncs() {
+        assert!(FlushPolicy::OnError.syncs(LogLevel::Error));
+        assert!(!FlushPolicy::OnError.syncs(LogLevel::Warning));
+        assert!(!FlushPolicy::EveryLine.syncs(LogLevel::Error));
+    }
+}
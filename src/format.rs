@@ -0,0 +1,725 @@
+// -------------------------------------------------------------------------------------------------
+//
+//  Implementation of a general purpose logger.
+//
+//  Copyright (c) 2025 by Dr. Panos Asproulis (p.asproulis@icloud.com).
+//  All Rights Reserved.
+//
+// -------------------------------------------------------------------------------------------------
+
+//! Implementation of the formatting options of the logged lines.
+
+use std::borrow::Cow;
+
+use crate::errors::LoggerError;
+use crate::levels::LogLevel;
+
+///
+/// The format of the logged lines.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum LogFormat {
+    ///
+    /// Plain text lines such as `[INFO] message key=value`.
+    ///
+    #[default]
+    Plain,
+    ///
+    /// Compact plain text lines for resource-constrained systems, where the
+    /// level is a single character without brackets, such as `I message`.
+    ///
+    Compact,
+    ///
+    /// One JSON object per line such as
+    /// `{"level":"INFO","message":"message","key":"value"}`.
+    ///
+    Json,
+    ///
+    /// Length-prefixed binary records, decoded by `read_binary_log`, for
+    /// high-volume logging. The fields are appended to the message as
+    /// `key=value` pairs and no header line is written. The console and
+    /// `format_line` render the records in the plain format.
+    ///
+    Binary,
+}
+
+///
+/// The label used for rendering the level of each logged line.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum LevelLabel {
+    ///
+    /// The full level names, e.g. `WARNING`.
+    ///
+    #[default]
+    Full,
+    ///
+    /// The abbreviated level names of at most five characters, e.g. `WARN`.
+    ///
+    Short,
+}
+
+//
+// Implementation of the `LevelLabel` enum.
+//
+impl LevelLabel {
+    ///
+    /// Render the label of a level.
+    ///
+    /// - Arguments:
+    ///   - `level`: The level to render.
+    ///
+    /// - Returns:
+    ///   - The rendered label of the level.
+    ///
+    pub fn render(&self, level: LogLevel) -> String {
+        match self {
+            LevelLabel::Full => level.to_level_string(),
+            LevelLabel::Short => level.to_short_level_string(),
+        }
+    }
+}
+
+///
+/// The line ending appended to each line written to the log file.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum LineEnding {
+    ///
+    /// Unix-style line feed (`\n`).
+    ///
+    #[default]
+    Lf,
+    ///
+    /// Windows-style carriage return and line feed (`\r\n`).
+    ///
+    CrLf,
+}
+
+//
+// Implementation of the `LineEnding` enum.
+//
+impl LineEnding {
+    ///
+    /// Get the characters of the line ending.
+    ///
+    /// - Returns:
+    ///   - The line ending as a string.
+    ///
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+///
+/// The placement of the line endings between the records.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum LineTermination {
+    ///
+    /// Every record is followed by a line ending.
+    ///
+    #[default]
+    Terminated,
+    ///
+    /// A line ending is written before every record except the first, so
+    /// that the log does not end with a line ending.
+    ///
+    Separated,
+}
+
+///
+/// The handling of the control characters contained in a message.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum ControlChars {
+    ///
+    /// Write the control characters verbatim.
+    ///
+    #[default]
+    Verbatim,
+    ///
+    /// Escape all the control characters except the newline, so that
+    /// multiline messages are still supported.
+    ///
+    Escape,
+    ///
+    /// Escape all the control characters including the newline.
+    ///
+    EscapeAll,
+}
+
+//
+// Implementation of the `ControlChars` enum.
+//
+impl ControlChars {
+    ///
+    /// Apply the control character handling to a message.
+    ///
+    /// - Arguments:
+    ///   - `message`: The message to sanitize.
+    ///
+    /// - Returns:
+    ///   - The sanitized message.
+    ///
+    pub fn apply(&self, message: &str) -> String {
+        if *self == ControlChars::Verbatim {
+            return message.to_string();
+        }
+
+        let mut sanitized = String::with_capacity(message.len());
+
+        for c in message.chars() {
+            match c {
+                '\n' if *self == ControlChars::Escape => sanitized.push(c),
+                '\n' => sanitized.push_str("\\n"),
+                '\r' => sanitized.push_str("\\r"),
+                '\t' => sanitized.push_str("\\t"),
+                '\0' => sanitized.push_str("\\0"),
+                c if c.is_control() && c.is_ascii() => {
+                    sanitized.push_str(&format!("\\x{:02x}", c as u32))
+                }
+                c if c.is_control() => sanitized.push_str(&format!("\\u{{{:x}}}", c as u32)),
+                c => sanitized.push(c),
+            }
+        }
+
+        sanitized
+    }
+}
+
+///
+/// The symbols prefixed to the console lines of each level.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelSymbols {
+    ///
+    /// The symbol of the `Info` level.
+    ///
+    pub info: String,
+    ///
+    /// The symbol of the `Debug` level.
+    ///
+    pub debug: String,
+    ///
+    /// The symbol of the `Warning` level.
+    ///
+    pub warning: String,
+    ///
+    /// The symbol of the `Error` level.
+    ///
+    pub error: String,
+}
+
+//
+// Implementation of the `LevelSymbols` struct.
+//
+impl LevelSymbols {
+    ///
+    /// Get the symbol of a level.
+    ///
+    /// - Arguments:
+    ///   - `level`: The level of the message.
+    ///
+    /// - Returns:
+    ///   - The symbol of the level.
+    ///
+    pub fn symbol(&self, level: LogLevel) -> &str {
+        match level {
+            LogLevel::Info => &self.info,
+            LogLevel::Debug => &self.debug,
+            LogLevel::Warning => &self.warning,
+            LogLevel::Error => &self.error,
+        }
+    }
+}
+
+//
+// Implementation of the `Default` trait for `LevelSymbols`.
+//
+impl Default for LevelSymbols {
+    ///
+    /// Create the default `LevelSymbols`.
+    ///
+    /// - Returns:
+    ///   - The default symbol set.
+    ///
+    fn default() -> Self {
+        Self {
+            info: "ℹ".to_string(),
+            debug: "🐛".to_string(),
+            warning: "⚠".to_string(),
+            error: "✖".to_string(),
+        }
+    }
+}
+
+///
+/// Get the ANSI escape sequence which selects the console color of a level.
+///
+/// - Arguments:
+///   - `level`: The level of the message.
+///
+/// - Returns:
+///   - The ANSI escape sequence of the color.
+///
+pub(crate) fn level_color(level: LogLevel) -> &'static str {
+    match level {
+        LogLevel::Info => "\x1b[32m",
+        LogLevel::Debug => "\x1b[36m",
+        LogLevel::Warning => "\x1b[33m",
+        LogLevel::Error => "\x1b[31m",
+    }
+}
+
+///
+/// The ANSI escape sequence which resets the console color.
+///
+pub(crate) const COLOR_RESET: &str = "\x1b[0m";
+
+///
+/// Remove the ANSI escape sequences from a string, e.g. the colors of a
+/// string formatted for a terminal. Control sequences (`ESC [ ... m`),
+/// operating system commands (`ESC ] ... BEL`) and two-character escapes
+/// are removed.
+///
+/// - Arguments:
+///   - `text`: The string to strip.
+///
+/// - Returns:
+///   - The string without escape sequences, borrowed if it had none.
+///
+pub fn strip_ansi(text: &str) -> Cow<'_, str> {
+    if !text.contains('\x1b') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            stripped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            //
+            // A control sequence ends with a byte in the range `@` to `~`.
+            //
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            //
+            // An operating system command ends with BEL or `ESC \`.
+            //
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+
+                    if c == '\x1b' {
+                        chars.next_if_eq(&'\\');
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Cow::Owned(stripped)
+}
+
+///
+/// The placeholders which may appear in a format template, one for each
+/// part of a rendered line.
+///
+pub const TEMPLATE_PLACEHOLDERS: &[&str] = &[
+    "seq",
+    "timestamp",
+    "uptime",
+    "delta",
+    "module",
+    "level",
+    "tag",
+    "message",
+    "fields",
+];
+
+///
+/// Validate the placeholders of a format template, such as
+/// `{timestamp} [{level}] {message}{fields}`, so that a typo is reported
+/// when the logger is configured rather than producing wrong lines. The
+/// placeholders are enclosed in braces, and `{{` and `}}` stand for literal
+/// braces.
+///
+/// - Arguments:
+///   - `template`: The template to validate.
+///
+/// - Returns:
+///   - Success if every placeholder is known, otherwise
+///     `LoggerError::InvalidTemplate` listing the unknown or unterminated
+///     placeholders.
+///
+pub fn validate_template(template: &str) -> Result<(), LoggerError> {
+    let mut invalid = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find(['{', '}']) {
+        let (brace, after) = (&rest[start..start + 1], &rest[start + 1..]);
+
+        if after.starts_with(brace) {
+            rest = &after[1..];
+            continue;
+        }
+
+        if brace == "}" {
+            invalid.push("}".to_string());
+            rest = after;
+            continue;
+        }
+
+        match after.find('}') {
+            Some(end) => {
+                let name = &after[..end];
+
+                if !TEMPLATE_PLACEHOLDERS.contains(&name) {
+                    invalid.push(format!("{{{}}}", name));
+                }
+
+                rest = &after[end + 1..];
+            }
+            None => {
+                invalid.push(format!("{{{}", after));
+                break;
+            }
+        }
+    }
+
+    if invalid.is_empty() {
+        Ok(())
+    } else {
+        Err(LoggerError::InvalidTemplate(invalid.join(", ")))
+    }
+}
+
+///
+/// Expand a validated format template, replacing each placeholder with
+/// its value and each `{{` or `}}` with a literal brace.
+///
+/// - Arguments:
+///   - `template`: The template to expand.
+///   - `value`: The function returning the value of a placeholder.
+///
+/// - Returns:
+///   - The expanded template.
+///
+pub(crate) fn expand_template(template: &str, value: impl Fn(&str) -> String) -> String {
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find(['{', '}']) {
+        let (brace, after) = (&rest[start..start + 1], &rest[start + 1..]);
+        expanded.push_str(&rest[..start]);
+
+        if after.starts_with(brace) || brace == "}" {
+            expanded.push_str(brace);
+            rest = after.strip_prefix(brace).unwrap_or(after);
+            continue;
+        }
+
+        match after.find('}') {
+            Some(end) => {
+                expanded.push_str(&value(&after[..end]));
+                rest = &after[end + 1..];
+            }
+            None => {
+                expanded.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+
+    expanded.push_str(rest);
+    expanded
+}
+
+///
+/// Escape a string for inclusion in a JSON string literal.
+///
+/// - Arguments:
+///   - `value`: The string to escape.
+///
+/// - Returns:
+///   - The escaped string, without the surrounding quotes.
+///
+pub(crate) fn escape_json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+///
+/// Escape a string for safe embedding in an HTML page, replacing the
+/// characters `&`, `<`, `>`, `"` and `'` with their entities.
+///
+/// - Arguments:
+///   - `value`: The string to escape.
+///
+/// - Returns:
+///   - The escaped string.
+///
+pub(crate) fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+///
+/// Quote a field value in the logfmt style if it is empty or contains
+/// whitespace, an equals sign, a quote or a control character, escaping
+/// the quotes, the backslashes and the control characters.
+///
+/// - Arguments:
+///   - `value`: The field value.
+///
+/// - Returns:
+///   - The value, quoted if necessary.
+///
+pub(crate) fn quote_value(value: &str) -> String {
+    let needs_quotes = value.is_empty()
+        || value
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || c == '=' || c == '"');
+
+    if needs_quotes {
+        format!("\"{}\"", escape_json(value))
+    } else {
+        value.to_string()
+    }
+}
+
+///
+/// The marker appended to a truncated message.
+///
+pub const TRUNCATION_MARKER: &str = "…[truncated]";
+
+///
+/// Truncate a message to a maximum number of characters. The length is
+/// counted in `char`s rather than bytes, so a multibyte UTF-8 sequence is
+/// never split.
+///
+/// - Arguments:
+///   - `message`: The message to truncate.
+///   - `max_len`: The maximum number of characters to keep.
+///
+/// - Returns:
+///   - The message, truncated and followed by `TRUNCATION_MARKER` if it
+///     was longer than `max_len` characters.
+///
+pub(crate) fn truncate_message(message: &str, max_len: usize) -> String {
+    match message.char_indices().nth(max_len) {
+        Some((index, _)) => format!("{}{}", &message[..index], TRUNCATION_MARKER),
+        None => message.to_string(),
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//
+// Unit Tests.
+//
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_ending_as_str() {
+        assert_eq!(LineEnding::Lf.as_str(), "\n");
+        assert_eq!(LineEnding::CrLf.as_str(), "\r\n");
+        assert_eq!(LineEnding::default(), LineEnding::Lf);
+    }
+
+    #[test]
+    fn test_control_chars_apply() {
+        let message = "a\x1b[31mb\rc\nd\0\u{85}";
+        assert_eq!(ControlChars::Verbatim.apply(message), message);
+        assert_eq!(
+            ControlChars::Escape.apply(message),
+            "a\\x1b[31mb\\rc\nd\\0\\u{85}"
+        );
+        assert_eq!(
+            ControlChars::EscapeAll.apply(message),
+            "a\\x1b[31mb\\rc\\nd\\0\\u{85}"
+        );
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html("<a href=\"x\">Tom & Jerry's</a>"),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;"
+        );
+        assert_eq!(escape_html("plain"), "plain");
+    }
+
+    #[test]
+    fn test_truncate_message() {
+        assert_eq!(truncate_message("short", 10), "short");
+        assert_eq!(truncate_message("exact", 5), "exact");
+        assert_eq!(
+            truncate_message("ααααα", 2),
+            format!("αα{}", TRUNCATION_MARKER)
+        );
+    }
+
+    #[test]
+    fn test_truncate_message_multibyte() {
+        //
+        // Characters of one to four bytes, so every cut falls next to a
+        // multibyte sequence.
+        //
+        let message = "aé€😀".repeat(8);
+
+        for max_len in 0..message.chars().count() {
+            let truncated = truncate_message(&message, max_len);
+            let bytes = truncated.into_bytes();
+            let text = String::from_utf8(bytes).expect("truncation split a character");
+            let kept = text.strip_suffix(TRUNCATION_MARKER).unwrap();
+
+            assert!(message.starts_with(kept));
+            assert_eq!(kept.chars().count(), max_len);
+        }
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert!(matches!(strip_ansi("plain"), Cow::Borrowed("plain")));
+        assert_eq!(
+            strip_ansi("\x1b[1;31mred\x1b[0m and \x1b[38;5;208morange\x1b[m"),
+            "red and orange"
+        );
+        assert_eq!(
+            strip_ansi("\x1b]0;title\x07text\x1b]8;;url\x1b\\link"),
+            "textlink"
+        );
+        assert_eq!(strip_ansi("a\x1bcb\x1b"), "ab");
+        assert_eq!(strip_ansi("é\x1b[2Kü"), "éü");
+    }
+
+    #[test]
+    fn test_validate_template() {
+        assert!(validate_template("{timestamp} [{level}] {message}{fields}").is_ok());
+        assert!(validate_template("{{literal}} {seq}").is_ok());
+        assert!(validate_template("").is_ok());
+
+        match validate_template("[{level}] {mesage} {feilds}") {
+            Err(LoggerError::InvalidTemplate(unknown)) => {
+                assert_eq!(unknown, "{mesage}, {feilds}")
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+        match validate_template("{message} } {level") {
+            Err(LoggerError::InvalidTemplate(unknown)) => assert_eq!(unknown, "}, {level"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_expand_template() {
+        let value = |name: &str| name.to_uppercase();
+
+        assert_eq!(
+            expand_template("[{level}] {message}{fields}", value),
+            "[LEVEL] MESSAGEFIELDS"
+        );
+        assert_eq!(expand_template("{{literal}} {seq}", value), "{literal} SEQ");
+        assert_eq!(expand_template("", value), "");
+    }
+
+    #[test]
+    fn test_quote_value() {
+        assert_eq!(quote_value("plain"), "plain");
+        assert_eq!(quote_value("two words"), "\"two words\"");
+        assert_eq!(quote_value("a=b"), "\"a=b\"");
+        assert_eq!(quote_value("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(quote_value(""), "\"\"");
+    }
+
+    #[test]
+    fn test_escape_json() {
+        assert_eq!(escape_json("plain"), "plain");
+        assert_eq!(
+            escape_json("say \"hi\"\\\n\x1b"),
+            "say \\\"hi\\\"\\\\\\n\\u001b"
+        );
+    }
+
+    #[test]
+    fn test_level_symbols() {
+        let mut symbols = LevelSymbols::default();
+        assert_eq!(symbols.symbol(LogLevel::Info), "ℹ");
+        assert_eq!(symbols.symbol(LogLevel::Error), "✖");
+
+        symbols.warning = "!".to_string();
+        assert_eq!(symbols.symbol(LogLevel::Warning), "!");
+    }
+}
@@ -9,10 +9,12 @@
 
 //! Implementation of the logging levels.
 
+use crate::errors::LoggerError;
+
 ///
 /// Logging levels.
 ///
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum LogLevel {
     ///
     /// Information level.
@@ -50,8 +52,115 @@ impl LogLevel {
             LogLevel::Error => "ERROR".to_string(),
         }
     }
+
+    ///
+    /// Convert the `LogLevel` to an abbreviated string representation of
+    /// at most five characters, which keeps the rendered levels aligned.
+    ///
+    /// - Returns:
+    ///   - The abbreviated string representation of the `LogLevel`.
+    ///
+    pub fn to_short_level_string(&self) -> String {
+        match self {
+            LogLevel::Warning => "WARN".to_string(),
+            _ => self.to_level_string(),
+        }
+    }
+
+    ///
+    /// Convert the `LogLevel` to a single-character representation.
+    ///
+    /// - Returns:
+    ///   - The first letter of the level name.
+    ///
+    pub fn to_level_char(&self) -> char {
+        match self {
+            LogLevel::Info => 'I',
+            LogLevel::Debug => 'D',
+            LogLevel::Warning => 'W',
+            LogLevel::Error => 'E',
+        }
+    }
+
+    ///
+    /// Get the severity of the `LogLevel`, where a higher value denotes a
+    /// more severe level.
+    ///
+    /// - Returns:
+    ///   - The severity of the `LogLevel`.
+    ///
+    pub const fn severity(&self) -> u8 {
+        match self {
+            LogLevel::Debug => 0,
+            LogLevel::Info => 1,
+            LogLevel::Warning => 2,
+            LogLevel::Error => 3,
+        }
+    }
+
+    ///
+    /// Check whether the `LogLevel` is enabled by the compile-time maximum
+    /// level selected through the `max_level_*` cargo features.
+    ///
+    /// - Returns:
+    ///   - True if messages of this level are compiled in.
+    ///
+    pub const fn is_statically_enabled(&self) -> bool {
+        self.severity() >= STATIC_MAX_LEVEL.severity()
+    }
+
+    ///
+    /// Map a command-line verbosity count, such as the number of `-v`
+    /// flags, to the least severe level which is logged: 0 gives `Error`,
+    /// 1 gives `Warning`, 2 gives `Info`, and 3 or more give `Debug`.
+    ///
+    /// - Arguments:
+    ///   - `verbosity`: The verbosity count.
+    ///
+    /// - Returns:
+    ///   - The corresponding `LogLevel`.
+    ///
+    pub const fn from_verbosity(verbosity: u8) -> LogLevel {
+        match verbosity {
+            0 => LogLevel::Error,
+            1 => LogLevel::Warning,
+            2 => LogLevel::Info,
+            _ => LogLevel::Debug,
+        }
+    }
+
+    ///
+    /// Get all the logging levels, ordered from the least to the most
+    /// severe.
+    ///
+    /// - Returns:
+    ///   - A slice with every `LogLevel` variant in severity order.
+    ///
+    pub const fn all() -> &'static [LogLevel] {
+        &[
+            LogLevel::Debug,
+            LogLevel::Info,
+            LogLevel::Warning,
+            LogLevel::Error,
+        ]
+    }
 }
 
+///
+/// The least severe level compiled into the logging macros. It is selected
+/// through the `max_level_info`, `max_level_warning` and `max_level_error`
+/// cargo features; if several are enabled, the most restrictive one wins.
+///
+pub const STATIC_MAX_LEVEL: LogLevel = if cfg!(feature = "max_level_error") {
+    LogLevel::Error
+} else if cfg!(feature = "max_level_warning") {
+    LogLevel::Warning
+} else if cfg!(feature = "max_level_info") {
+    LogLevel::Info
+} else {
+    LogLevel::Debug
+};
+
 //
 // Implementation of the `std::fmt::Display` trait for `LogLevel`.
 //
@@ -70,6 +179,61 @@ impl std::fmt::Display for LogLevel {
     }
 }
 
+//
+// Implementation of the `std::str::FromStr` trait for `LogLevel`.
+//
+impl std::str::FromStr for LogLevel {
+    type Err = LoggerError;
+
+    ///
+    /// Parse a `LogLevel` from its string representation. The comparison
+    /// is case-insensitive.
+    ///
+    /// - Arguments:
+    ///   - `s`: The string to parse.
+    ///
+    /// - Returns:
+    ///   - The parsed `LogLevel` or `LoggerError::InvalidLevel`.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "INFO" => Ok(LogLevel::Info),
+            "DEBUG" => Ok(LogLevel::Debug),
+            "WARNING" => Ok(LogLevel::Warning),
+            "ERROR" => Ok(LogLevel::Error),
+            _ => Err(LoggerError::InvalidLevel(s.to_string())),
+        }
+    }
+}
+
+//
+// Implementation of the `serde::Serialize` trait for `LogLevel`.
+//
+#[cfg(feature = "serde")]
+impl serde::Serialize for LogLevel {
+    ///
+    /// Serialize the `LogLevel` as its string representation.
+    ///
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_level_string())
+    }
+}
+
+//
+// Implementation of the `serde::Deserialize` trait for `LogLevel`.
+//
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for LogLevel {
+    ///
+    /// Deserialize the `LogLevel` from its case-insensitive string
+    /// representation.
+    ///
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let level = String::deserialize(deserializer)?;
+        level.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 // -------------------------------------------------------------------------------------------------
 //
 // Unit Tests.
@@ -79,6 +243,7 @@ impl std::fmt::Display for LogLevel {
 #[cfg(test)]
 mod tests {
     use super::LogLevel;
+    use crate::errors::LoggerError;
 
     #[test]
     fn test_to_level_string() {
@@ -88,6 +253,22 @@ mod tests {
         assert_eq!(LogLevel::Error.to_level_string(), "ERROR");
     }
 
+    #[test]
+    fn test_to_short_level_string() {
+        assert_eq!(LogLevel::Info.to_short_level_string(), "INFO");
+        assert_eq!(LogLevel::Debug.to_short_level_string(), "DEBUG");
+        assert_eq!(LogLevel::Warning.to_short_level_string(), "WARN");
+        assert_eq!(LogLevel::Error.to_short_level_string(), "ERROR");
+    }
+
+    #[test]
+    fn test_to_level_char() {
+        assert_eq!(LogLevel::Info.to_level_char(), 'I');
+        assert_eq!(LogLevel::Debug.to_level_char(), 'D');
+        assert_eq!(LogLevel::Warning.to_level_char(), 'W');
+        assert_eq!(LogLevel::Error.to_level_char(), 'E');
+    }
+
     #[test]
     fn test_display_trait() {
         assert_eq!(format!("{}", LogLevel::Info), "INFO");
@@ -96,6 +277,54 @@ mod tests {
         assert_eq!(format!("{}", LogLevel::Error), "ERROR");
     }
 
+    #[test]
+    fn test_from_str() {
+        assert_eq!("INFO".parse::<LogLevel>().unwrap(), LogLevel::Info);
+        assert_eq!("debug".parse::<LogLevel>().unwrap(), LogLevel::Debug);
+        assert_eq!("Warning".parse::<LogLevel>().unwrap(), LogLevel::Warning);
+        assert_eq!("ERROR".parse::<LogLevel>().unwrap(), LogLevel::Error);
+        assert!(matches!(
+            "LOUD".parse::<LogLevel>(),
+            Err(LoggerError::InvalidLevel(level)) if level == "LOUD"
+        ));
+    }
+
+    #[test]
+    fn test_severity() {
+        assert!(LogLevel::Debug.severity() < LogLevel::Info.severity());
+        assert!(LogLevel::Info.severity() < LogLevel::Warning.severity());
+        assert!(LogLevel::Warning.severity() < LogLevel::Error.severity());
+        assert!(LogLevel::Error.is_statically_enabled());
+    }
+
+    #[test]
+    fn test_from_verbosity() {
+        assert_eq!(LogLevel::from_verbosity(0), LogLevel::Error);
+        assert_eq!(LogLevel::from_verbosity(1), LogLevel::Warning);
+        assert_eq!(LogLevel::from_verbosity(2), LogLevel::Info);
+        assert_eq!(LogLevel::from_verbosity(3), LogLevel::Debug);
+        assert_eq!(LogLevel::from_verbosity(u8::MAX), LogLevel::Debug);
+    }
+
+    #[test]
+    fn test_all() {
+        let levels = LogLevel::all();
+        assert_eq!(levels.len(), 4);
+        for level in [
+            LogLevel::Info,
+            LogLevel::Debug,
+            LogLevel::Warning,
+            LogLevel::Error,
+        ] {
+            assert!(levels.contains(&level));
+        }
+        assert!(
+            levels
+                .windows(2)
+                .all(|pair| pair[0].severity() < pair[1].severity())
+        );
+    }
+
     #[test]
     fn test_enum_equality() {
         assert!(LogLevel::Info == LogLevel::Info);
@@ -7,8 +7,40 @@
 //
 // -------------------------------------------------------------------------------------------------
 
+pub mod async_logger;
+pub mod binary;
+pub mod clock;
+pub mod config;
+mod destination;
+pub mod errors;
+pub mod flush;
+pub mod format;
 pub mod levels;
+pub mod local;
 pub mod logger;
+mod macros;
+pub mod reader;
+pub mod rotation;
+pub mod sink;
+pub mod stats;
+#[cfg(any(test, feature = "test-util"))]
+pub mod testing;
+pub mod timestamp;
+pub mod writer;
 
+pub use async_logger::*;
+pub use binary::*;
+pub use clock::*;
+pub use config::*;
+pub use errors::*;
+pub use flush::*;
+pub use format::*;
 pub use levels::*;
+pub use local::*;
 pub use logger::*;
+pub use reader::*;
+pub use rotation::*;
+pub use sink::*;
+pub use stats::*;
+pub use timestamp::*;
+pub use writer::*;
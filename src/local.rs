@@ -0,0 +1,297 @@
+// -------------------------------------------------------------------------------------------------
+//
+//  Implementation of a general purpose logger.
+//
+//  Copyright (c) 2025 by Dr. Panos Asproulis (p.asproulis@icloud.com).
+//  All Rights Reserved.
+//
+// -------------------------------------------------------------------------------------------------
+
+//! Implementation of the single-threaded logger, which uses `RefCell` and
+//! `Cell` instead of `Mutex` and atomics, so it is not `Sync`.
+
+use std::cell::{Cell, RefCell};
+use std::fs::File;
+use std::io::prelude::*;
+use std::sync::Arc;
+
+use crate::destination::create_log_file;
+use crate::errors::LoggerError;
+use crate::levels::LogLevel;
+use crate::logger::{LogTerminateHook, Logger, Shared, resolve_log_path, terminate_process};
+use crate::stats::LevelCounts;
+
+///
+/// A logger for use from a single thread, which avoids the cost of the
+/// locking of `Logger`. It writes the records in the plain format, without
+/// timestamps, to its log file and the standard output, and supports the
+/// minimum level, the per-level counts and the termination on errors,
+/// with the exit code and the hook of `Logger`. The other options of
+/// `Logger`, such as the formats, the timestamps, the flush policies, the
+/// rotation and the filters, are not supported.
+///
+#[derive(Debug)]
+pub struct LocalLogger {
+    ///
+    /// The complete path of the file to use for logging.
+    ///
+    log_file: String,
+    ///
+    /// The open log file.
+    ///
+    file: RefCell<File>,
+    ///
+    /// If true then terminate the application when an error message is
+    /// logged.
+    ///
+    terminate_on_error: bool,
+    ///
+    /// The exit code of the process when it is terminated because of a
+    /// logged error.
+    ///
+    error_exit_code: i32,
+    ///
+    /// The hook invoked before the application is terminated.
+    ///
+    on_terminate: Option<Shared<LogTerminateHook>>,
+    ///
+    /// The least severe level of the messages which are logged.
+    ///
+    min_level: Cell<LogLevel>,
+    ///
+    /// The number of messages logged at each level.
+    ///
+    counts: Cell<LevelCounts>,
+}
+
+//
+// Implementation of the single-threaded constructor of `Logger`.
+//
+impl Logger {
+    ///
+    /// Create a new `LocalLogger` for use from a single thread.
+    ///
+    /// - Arguments:
+    ///   - `log_file_path`: The complete path of the file to use for logging.
+    ///   - `terminate_on_error`: If true then terminate the application when
+    ///     an error message is logged.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `LocalLogger` object or the error which
+    ///     occurred.
+    ///
+    pub fn single_threaded(
+        log_file_path: &str,
+        terminate_on_error: bool,
+    ) -> Result<LocalLogger, LoggerError> {
+        let log_file = resolve_log_path(log_file_path)?;
+        let file = create_log_file(std::path::Path::new(&log_file))?;
+
+        Ok(LocalLogger {
+            log_file,
+            file: RefCell::new(file),
+            terminate_on_error,
+            error_exit_code: 1,
+            on_terminate: None,
+            min_level: Cell::new(LogLevel::Debug),
+            counts: Cell::new(LevelCounts::default()),
+        })
+    }
+}
+
+//
+// Implementation of the `LocalLogger` struct.
+//
+impl LocalLogger {
+    ///
+    /// Get the complete path of the log file.
+    ///
+    /// - Returns:
+    ///   - The path of the log file.
+    ///
+    pub fn log_file(&self) -> &str {
+        &self.log_file
+    }
+
+    ///
+    /// Set the least severe level of the messages which are logged.
+    ///
+    /// - Arguments:
+    ///   - `min_level`: The minimum level to log.
+    ///
+    pub fn set_min_level(&self, min_level: LogLevel) {
+        self.min_level.set(min_level);
+    }
+
+    ///
+    /// Set the exit code of the process when it is terminated because of a
+    /// logged error. The default is 1.
+    ///
+    /// - Arguments:
+    ///   - `error_exit_code`: The exit code to use.
+    ///
+    pub fn set_error_exit_code(&mut self, error_exit_code: i32) {
+        self.error_exit_code = error_exit_code;
+    }
+
+    ///
+    /// Set a hook which is invoked before the application is terminated
+    /// because of a logged error.
+    ///
+    /// - Arguments:
+    ///   - `on_terminate`: The hook to invoke.
+    ///
+    pub fn set_on_terminate(&mut self, on_terminate: Box<LogTerminateHook>) {
+        self.on_terminate = Some(Shared(Arc::from(on_terminate)));
+    }
+
+    ///
+    /// Get the number of messages logged at each level.
+    ///
+    /// - Returns:
+    ///   - The per-level message counts.
+    ///
+    pub fn counts(&self) -> LevelCounts {
+        self.counts.get()
+    }
+
+    ///
+    /// Log a message.
+    ///
+    /// - Arguments:
+    ///   - `level`: The level of the message.
+    ///   - `message`: The message to log.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    pub fn log(&self, level: LogLevel, message: &str) -> Result<(), LoggerError> {
+        if level.severity() < self.min_level.get().severity() {
+            return Ok(());
+        }
+
+        let line = format!("[{}] {}", level, message);
+        self.file
+            .borrow_mut()
+            .write_all(format!("{}\n", line).as_bytes())?;
+        println!("{}", line);
+
+        let mut counts = self.counts.get();
+
+        match level {
+            LogLevel::Info => counts.info += 1,
+            LogLevel::Debug => counts.debug += 1,
+            LogLevel::Warning => counts.warning += 1,
+            LogLevel::Error => counts.error += 1,
+        }
+
+        self.counts.set(counts);
+        //
+        // If the level is `Error`, then we need to terminate the application.
+        //
+        if level == LogLevel::Error && self.terminate_on_error {
+            let _ = self.file.borrow_mut().flush();
+            terminate_process(
+                self.on_terminate.as_ref().map(|hook| &*hook.0),
+                self.error_exit_code,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//
+// Unit Tests.
+//
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+    use std::fs;
+
+    //
+    // Helper function to generate unique log filenames for tests
+    //
+    fn get_unique_log_filename() -> String {
+        let mut rng = rand::rng();
+        format!("test_log_{}.log", rng.random::<u64>())
+    }
+
+    //
+    // A trait which is implemented ambiguously for `Sync` types, so that
+    // naming its item only compiles for types which are not `Sync`.
+    //
+    trait AmbiguousIfSync<A> {
+        fn check() {}
+    }
+
+    impl<T: ?Sized> AmbiguousIfSync<()> for T {}
+    impl<T: ?Sized + Sync> AmbiguousIfSync<u8> for T {}
+
+    #[test]
+    fn test_local_logger_is_not_sync() {
+        <LocalLogger as AmbiguousIfSync<_>>::check();
+    }
+
+    #[test]
+    fn test_local_logger() {
+        let log_file = get_unique_log_filename();
+        let logger = Logger::single_threaded(&log_file, false).unwrap();
+        logger.set_min_level(LogLevel::Info);
+
+        logger.log(LogLevel::Debug, "Dropped").unwrap();
+        logger.log(LogLevel::Info, "Kept").unwrap();
+        logger.log(LogLevel::Error, "Failed").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "[INFO] Kept\n[ERROR] Failed\n"
+        );
+        assert_eq!(logger.counts().total(), 2);
+        assert_eq!(logger.log_file(), log_file);
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_local_logger_error_exit_code() {
+        //
+        // The child process logs an error with a custom exit code.
+        //
+        if let Ok(log_file) = std::env::var("LOGGER_LOCAL_EXIT_CODE_CHILD") {
+            let mut logger = Logger::single_threaded(&log_file, true).unwrap();
+            logger.set_error_exit_code(70);
+            logger.set_on_terminate(Box::new(|| eprintln!("on_terminate invoked")));
+            let _ = logger.log(LogLevel::Error, "Fatal condition");
+            unreachable!("Logger: the process was not terminated.");
+        }
+
+        let log_file = get_unique_log_filename();
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "local::tests::test_local_logger_error_exit_code",
+                "--exact",
+                "--nocapture",
+            ])
+            .env("LOGGER_LOCAL_EXIT_CODE_CHILD", &log_file)
+            .output()
+            .unwrap();
+
+        assert_eq!(output.status.code(), Some(70));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("on_terminate invoked"));
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "[ERROR] Fatal condition\n"
+        );
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+}
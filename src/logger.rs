@@ -12,11 +12,155 @@
 //! Copyright (c) 2025 by Dr. Panos Asproulis (p.asproulis@icloud.com).
 //! All Rights Reserved.
 
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fmt::Display;
 use std::fs::File;
 use std::fs::OpenOptions;
+use std::io::SeekFrom;
 use std::io::prelude::*;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, TryLockError};
+use std::time::{Instant, SystemTime};
 
+use crate::binary::encode_record;
+use crate::clock::{Clock, SystemClock};
+use crate::config::LoggerConfig;
+use crate::destination::{Destination, Spool, Target, create_log_file};
+use crate::errors::{LoggerError, WriteFailurePolicy};
+use crate::flush::FlushPolicy;
+use crate::format::{
+    COLOR_RESET, ControlChars, LevelLabel, LevelSymbols, LineEnding, LineTermination, LogFormat,
+    escape_html, escape_json, expand_template, level_color, quote_value, strip_ansi,
+    truncate_message, validate_template,
+};
 use crate::levels::LogLevel;
+use crate::rotation::{RotationPeriod, period_path, rotate_backups};
+use crate::stats::{LevelCounters, LevelCounts, LoggerStats, OutputCounters};
+use crate::timestamp::{TimestampFormat, format_delta, format_uptime};
+
+///
+/// The byte order mark written at the start of new UTF-8 log files, if
+/// enabled.
+///
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+///
+/// The panic message when a record cannot be written to the log
+/// destination and the write failure policy is `WriteFailurePolicy::Panic`.
+///
+const WRITE_ERROR: &str = "Logger: I cannot write to the log file.";
+
+///
+/// A user-supplied object, such as a callback or a writer, shared by all
+/// the clones of a `Logger`.
+///
+pub(crate) struct Shared<T: ?Sized>(pub(crate) Arc<T>);
+
+//
+// Implementation of the `Clone` trait for `Shared`.
+//
+impl<T: ?Sized> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+//
+// Implementation of the `std::fmt::Debug` trait for `Shared`.
+//
+impl<T: ?Sized> std::fmt::Debug for Shared<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Shared")
+    }
+}
+
+///
+/// The type of the filter callback which decides whether a record is logged.
+///
+pub type LogFilter = dyn Fn(LogLevel, &str) -> bool + Send + Sync;
+
+///
+/// The type of the redactor callback which masks sensitive parts of a message.
+///
+pub type LogRedactor = dyn Fn(&str) -> String + Send + Sync;
+
+///
+/// The type of the callback which produces the header of a new log file.
+///
+pub type LogHeader = dyn Fn() -> String + Send + Sync;
+
+///
+/// The type of the hook invoked right before the logger terminates the
+/// application.
+///
+pub type LogTerminateHook = dyn Fn() + Send + Sync;
+
+///
+/// The type of the writer receiving the console lines instead of the
+/// standard output.
+///
+type ConsoleWriter = Mutex<Box<dyn Write + Send>>;
+
+///
+/// A record prepared for rendering, so that it is rendered identically,
+/// apart from its format, on the console and the log destination.
+///
+struct Record<'a> {
+    ///
+    /// The level of the record.
+    ///
+    level: LogLevel,
+    ///
+    /// The time at which the record was logged.
+    ///
+    time: SystemTime,
+    ///
+    /// The time elapsed since the creation of the `Logger`.
+    ///
+    uptime: std::time::Duration,
+    ///
+    /// The processed message of the record.
+    ///
+    message: String,
+    ///
+    /// The fields of the record in their output order.
+    ///
+    fields: Vec<(&'a str, String)>,
+    ///
+    /// The sequence number of the record, if enabled and assigned.
+    ///
+    sequence: Option<u64>,
+    ///
+    /// The module path of the caller, if logged through a macro and enabled.
+    ///
+    module: Option<&'static str>,
+    ///
+    /// The time elapsed since the previous record, if enabled.
+    ///
+    delta: Option<std::time::Duration>,
+}
+
+///
+/// The last logged record, used for collapsing consecutive identical records.
+///
+#[derive(Debug)]
+struct LastRecord {
+    ///
+    /// The level of the record.
+    ///
+    level: LogLevel,
+    ///
+    /// The message of the record.
+    ///
+    message: String,
+    ///
+    /// The number of times the record was repeated since it was written.
+    ///
+    repeats: u64,
+}
 
 ///
 /// This struct is responsible for logging the messages produced
@@ -29,10 +173,254 @@ pub struct Logger {
     ///
     log_file: String,
     ///
+    /// The open destination of the logged lines, shared by all the clones
+    /// of this `Logger`.
+    ///
+    destination: Shared<Mutex<Destination>>,
+    ///
     /// If true then terminate the application when an error
-    /// message is logged.
+    /// message is logged. The flag is shared by all the clones of this
+    /// `Logger`.
+    ///
+    terminate_on_error: Arc<AtomicBool>,
+    ///
+    /// Key-value context fields appended to every logged message.
+    ///
+    context: Vec<(String, String)>,
+    ///
+    /// The number of messages logged at each level, shared by all the
+    /// clones of this `Logger`.
+    ///
+    counters: Arc<LevelCounters>,
+    ///
+    /// The format of the timestamp prefixed to each logged message.
+    ///
+    timestamp_format: TimestampFormat,
+    ///
+    /// Whether the records of each level, indexed by severity, carry a
+    /// timestamp.
+    ///
+    timestamp_levels: [bool; 4],
+    ///
+    /// The timezone in which the timestamps are rendered, or `None` for UTC.
+    ///
+    #[cfg(feature = "timezone")]
+    timezone: Option<chrono_tz::Tz>,
+    ///
+    /// The clock used for timestamping the logged messages.
+    ///
+    clock: Arc<dyn Clock>,
+    ///
+    /// The instant of the creation of this `Logger`, used for the uptime
+    /// timestamps.
+    ///
+    start: Instant,
+    ///
+    /// An optional filter which drops the records for which it returns false.
+    ///
+    filter: Option<Shared<LogFilter>>,
+    ///
+    /// An optional redactor which masks sensitive parts of each message.
+    ///
+    redactor: Option<Shared<LogRedactor>>,
+    ///
+    /// The policy deciding when the buffered records are written to the
+    /// log destination.
+    ///
+    flush_policy: FlushPolicy,
+    ///
+    /// If true then the missing parent directories of the log file were
+    /// created by the construction.
+    ///
+    create_dirs: bool,
+    ///
+    /// If true then the log file is only created on the first write.
+    ///
+    lazy: bool,
+    ///
+    /// The line ending appended to each line written to the log file.
+    ///
+    line_ending: LineEnding,
+    ///
+    /// The handling of the control characters contained in each message.
+    ///
+    control_chars: ControlChars,
+    ///
+    /// If true then the HTML special characters of each message are escaped.
+    ///
+    escape_html: bool,
+    ///
+    /// If true then the process id is included in each logged line.
+    ///
+    include_pid: bool,
+    ///
+    /// If true then the module path of the callers of the logging macros
+    /// is included in each logged line.
+    ///
+    include_module_path: bool,
+    ///
+    /// The hostname included in each logged line, if enabled.
+    ///
+    hostname: Option<String>,
+    ///
+    /// The tag inserted between the level and the message of each line.
+    ///
+    tag: Option<String>,
+    ///
+    /// If true then logging is suspended and the records are dropped. The
+    /// flag is shared by all the clones of this `Logger`.
+    ///
+    suspended: Arc<AtomicBool>,
+    ///
+    /// The maximum number of characters of a message, if limited.
+    ///
+    max_message_len: Option<usize>,
+    ///
+    /// The least severe level of the messages which are logged.
+    ///
+    min_level: LogLevel,
+    ///
+    /// The severity of the level overriding the minimum level while a
+    /// closure passed to `with_verbose` runs, or `NO_OVERRIDE`. The
+    /// override is shared by all the clones of this `Logger`.
+    ///
+    verbose_severity: Arc<AtomicU8>,
+    ///
+    /// An optional callback producing the header line of each newly
+    /// created log file.
+    ///
+    header: Option<Shared<LogHeader>>,
+    ///
+    /// The format of the logged lines.
+    ///
+    format: LogFormat,
+    ///
+    /// The optional symbols prefixed to the console lines of each level.
+    ///
+    console_symbols: Option<LevelSymbols>,
+    ///
+    /// If true then the console lines are colored according to their level.
+    ///
+    console_colors: bool,
+    ///
+    /// The label used for rendering the level of each logged line.
+    ///
+    level_label: LevelLabel,
+    ///
+    /// The labels overriding the level label for specific levels.
+    ///
+    level_labels: HashMap<LogLevel, String>,
+    ///
+    /// The last logged record, if consecutive identical records are
+    /// collapsed. It is shared by all the clones of this `Logger`.
+    ///
+    last_record: Option<Arc<Mutex<Option<LastRecord>>>>,
+    ///
+    /// An optional hook invoked right before the application is terminated.
     ///
-    terminate_on_error: bool,
+    on_terminate: Option<Shared<LogTerminateHook>>,
+    ///
+    /// The prefix of the continuation lines of multiline messages, if any.
+    ///
+    continuation_prefix: Option<String>,
+    ///
+    /// The template of the plain lines, if any.
+    ///
+    format_template: Option<String>,
+    ///
+    /// The format of the console lines, if different from the format of
+    /// the lines written to the log destination.
+    ///
+    console_format: Option<LogFormat>,
+    ///
+    /// An optional writer receiving the console lines instead of the
+    /// standard output.
+    ///
+    console: Option<Shared<ConsoleWriter>>,
+    ///
+    /// If true then the console is flushed after each line.
+    ///
+    flush_console: bool,
+    ///
+    /// The size in bytes above which the log file is rotated, if enabled.
+    ///
+    max_file_size: Option<u64>,
+    ///
+    /// The maximum number of numbered backups kept by the rotation.
+    ///
+    max_backups: usize,
+    ///
+    /// The sampling rate of each level, indexed by severity, where a rate
+    /// of N keeps one in every N records.
+    ///
+    sample_rates: [u64; 4],
+    ///
+    /// The number of records of each level seen by the sampling, shared by
+    /// all the clones of this `Logger`.
+    ///
+    sample_counters: Arc<LevelCounters>,
+    ///
+    /// The period after which logging switches to a new file, if enabled.
+    ///
+    rotation_period: Option<RotationPeriod>,
+    ///
+    /// If true then a UTF-8 byte order mark is written at the start of
+    /// each new log file.
+    ///
+    write_bom: bool,
+    ///
+    /// The separator written before each key-value field in the plain
+    /// formats.
+    ///
+    field_separator: char,
+    ///
+    /// If true then the field values are quoted in the logfmt style when
+    /// necessary.
+    ///
+    quote_values: bool,
+    ///
+    /// The placement of the line endings between the records.
+    ///
+    line_termination: LineTermination,
+    ///
+    /// The exit code of the process when it is terminated because of a
+    /// logged error.
+    ///
+    error_exit_code: i32,
+    ///
+    /// The behaviour when a record cannot be written to the log destination.
+    ///
+    write_failure_policy: WriteFailurePolicy,
+    ///
+    /// The number of bytes and the time of the written records, shared by
+    /// all the clones of this `Logger`.
+    ///
+    output_counters: Arc<OutputCounters>,
+    ///
+    /// The minimum number of digits of the sequence numbers, if enabled.
+    ///
+    sequence_width: Option<usize>,
+    ///
+    /// The number of records which were assigned a sequence number, shared
+    /// by all the clones of this `Logger`.
+    ///
+    sequence: Arc<AtomicU64>,
+    ///
+    /// If true then each record includes the time elapsed since the
+    /// previous one.
+    ///
+    include_delta: bool,
+    ///
+    /// The instant of the previous record, shared by all the clones of this
+    /// `Logger`, if the deltas are enabled.
+    ///
+    last_instant: Arc<Mutex<Option<Instant>>>,
+    ///
+    /// The `Warning` and `Error` lines logged by this `Logger` and its
+    /// clones, kept for `assert_clean`.
+    ///
+    #[cfg(any(test, feature = "test-util"))]
+    pub(crate) issues: Arc<Mutex<Vec<String>>>,
 }
 
 //
@@ -48,255 +436,5610 @@ impl Logger {
     /// - Returns:
     ///   - The newly constructed `Logger` object.
     ///
+    /// - Panics:
+    ///   - If the log file cannot be created.
+    ///
     pub fn new(log_file_path: &str, terminate_on_error: bool) -> Self {
+        match Self::try_new(log_file_path, terminate_on_error) {
+            Ok(logger) => logger,
+            Err(error) => {
+                panic!("Logger: I cannot create the log file: {:?}", error)
+            }
+        }
+    }
+
+    ///
+    /// Create a new `Logger`, returning an error if the log file cannot
+    /// be created, or `LoggerError::PathIsDirectory` if its path is an
+    /// existing directory.
+    ///
+    /// - Arguments:
+    ///   - `log_file_path`: The complete path of the file to use for logging.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `Logger` object or the error which occurred.
+    ///
+    pub fn try_new(log_file_path: &str, terminate_on_error: bool) -> Result<Self, LoggerError> {
+        Self::open(log_file_path, |_| true, terminate_on_error)
+    }
+
+    ///
+    /// Create a new `Logger` like `try_new`, first creating the missing
+    /// parent directories of the log file, e.g. `logs/` for
+    /// `logs/app.log`.
+    ///
+    /// - Arguments:
+    ///   - `log_file_path`: The complete path of the file to use for logging.
+    ///   - `terminate_on_error`: If true then terminate the application when
+    ///     an error message is logged.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `Logger` object or the error which occurred.
+    ///
+    pub fn try_new_with_dirs(
+        log_file_path: &str,
+        terminate_on_error: bool,
+    ) -> Result<Self, LoggerError> {
+        create_parent_dirs(log_file_path)?;
+
+        let mut logger = Self::try_new(log_file_path, terminate_on_error)?;
+        logger.create_dirs = true;
+
+        Ok(logger)
+    }
+
+    ///
+    /// Create a new `Logger` with the construction options of a
+    /// configuration.
+    ///
+    /// - Arguments:
+    ///   - `log_file_path`: The complete path of the file to use for logging.
+    ///   - `lazy`: If true then the log file is only created on the first
+    ///     write.
+    ///   - `create_dirs`: If true then the missing parent directories of the
+    ///     log file are created.
+    ///   - `terminate_on_error`: If true then terminate the application when
+    ///     an error message is logged.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `Logger` object or the error which occurred.
+    ///
+    pub(crate) fn open_configured(
+        log_file_path: &str,
+        lazy: bool,
+        create_dirs: bool,
+        terminate_on_error: bool,
+    ) -> Result<Self, LoggerError> {
+        match (lazy, create_dirs) {
+            (false, false) => Self::try_new(log_file_path, terminate_on_error),
+            (false, true) => Self::try_new_with_dirs(log_file_path, terminate_on_error),
+            (true, _) => {
+                if create_dirs {
+                    create_parent_dirs(log_file_path)?;
+                }
+
+                let mut logger = Self::try_new_lazy(log_file_path, terminate_on_error)?;
+                logger.create_dirs = create_dirs;
+
+                Ok(logger)
+            }
+        }
+    }
+
+    ///
+    /// Create a new `Logger` which creates its log file lazily, on the
+    /// first record written to it, so that no empty file is left behind
+    /// by a process which never logs, and the logger may be constructed
+    /// before the directory of the log file exists.
+    ///
+    /// - Arguments:
+    ///   - `log_file_path`: The complete path of the file to use for logging.
+    ///   - `terminate_on_error`: If true then terminate the application when
+    ///     an error message is logged.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `Logger` object, or
+    ///     `LoggerError::PathIsDirectory` if the path is an existing
+    ///     directory.
+    ///
+    pub fn try_new_lazy(
+        log_file_path: &str,
+        terminate_on_error: bool,
+    ) -> Result<Self, LoggerError> {
+        let log_file = resolve_log_path(log_file_path)?;
+
+        let target = Target::Lazy(PathBuf::from(&log_file));
+        let mut logger =
+            Self::with_destination(log_file, Destination::new(target), terminate_on_error);
+        logger.lazy = true;
+
+        Ok(logger)
+    }
+
+    ///
+    /// Create a new `Logger` which writes each record to its own file in a
+    /// spool directory, e.g. `spool/000001.log`, for a downstream process
+    /// which consumes and deletes the individual files. The directory is
+    /// created if it does not exist, and the sequence numbers of existing
+    /// files are skipped, so no file is ever overwritten.
+    ///
+    /// - Arguments:
+    ///   - `directory`: The path of the spool directory.
+    ///   - `terminate_on_error`: If true then terminate the application when
+    ///     an error message is logged.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `Logger` object or the error which occurred.
+    ///
+    pub fn spool(directory: &str, terminate_on_error: bool) -> Result<Self, LoggerError> {
+        let spool = Spool::new(Path::new(directory))?;
+
+        Ok(Self::with_destination(
+            directory.to_string(),
+            Destination::new(Target::Spool(spool)),
+            terminate_on_error,
+        ))
+    }
+
+    ///
+    /// Create a new `Logger` which truncates the existing log file if it was
+    /// last modified longer than `max_age` ago and appends to it otherwise.
+    /// This keeps the recent history across quick restarts, while a file
+    /// left over from a long gap starts afresh.
+    ///
+    /// - Arguments:
+    ///   - `log_file_path`: The complete path of the file to use for logging.
+    ///   - `max_age`: The age above which the log file is truncated.
+    ///   - `terminate_on_error`: If true then terminate the application when
+    ///     an error message is logged.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `Logger` object or the error which occurred.
+    ///
+    pub fn open_with_max_age(
+        log_file_path: &str,
+        max_age: std::time::Duration,
+        terminate_on_error: bool,
+    ) -> Result<Self, LoggerError> {
+        Self::open(
+            log_file_path,
+            |path| {
+                std::fs::metadata(path)
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+                    .and_then(|modified| modified.elapsed().ok())
+                    .is_some_and(|age| age > max_age)
+            },
+            terminate_on_error,
+        )
+    }
+
+    ///
+    /// Create a new `Logger` for a log file, truncating the file if it
+    /// exists and the predicate holds for it.
+    ///
+    /// - Arguments:
+    ///   - `log_file_path`: The complete path of the file to use for logging.
+    ///   - `truncate`: The predicate deciding whether to truncate the file.
+    ///   - `terminate_on_error`: If true then terminate the application when
+    ///     an error message is logged.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `Logger` object or the error which occurred.
+    ///
+    fn open(
+        log_file_path: &str,
+        truncate: impl FnOnce(&Path) -> bool,
+        terminate_on_error: bool,
+    ) -> Result<Self, LoggerError> {
         //
         // Create the logging file and verify the success of the operation.
         //
-        let log_file = if log_file_path.is_empty() {
-            "default.log".to_string()
-        } else {
-            log_file_path.to_string()
-        };
+        let log_file = resolve_log_path(log_file_path)?;
+
+        if truncate(Path::new(&log_file)) {
+            File::create(&log_file)?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_file)?;
+        //
+        // Create and return the Logger.
+        //
+        Ok(Self::with_destination(
+            log_file,
+            Destination::new(Target::File(file)),
+            terminate_on_error,
+        ))
+    }
+
+    ///
+    /// Create a new `Logger` which logs exclusively to the console, e.g.
+    /// for command-line tools. No file is created or written.
+    ///
+    /// - Arguments:
+    ///   - `terminate_on_error`: If true then terminate the application when
+    ///     an error message is logged.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `Logger` object.
+    ///
+    pub fn stdout_only(terminate_on_error: bool) -> Self {
+        Self::from_writer(std::io::sink(), terminate_on_error)
+    }
+
+    ///
+    /// Create a new `Logger` which writes to an arbitrary writer, such as
+    /// an in-memory buffer, a pipe or a network socket, instead of a file.
+    ///
+    /// - Arguments:
+    ///   - `writer`: The writer to use as the log destination.
+    ///   - `terminate_on_error`: If true then terminate the application when
+    ///     an error message is logged.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `Logger` object.
+    ///
+    pub fn from_writer<W: Write + Send + 'static>(writer: W, terminate_on_error: bool) -> Self {
+        Self::with_destination(
+            String::new(),
+            Destination::new(Target::Writer(Box::new(writer))),
+            terminate_on_error,
+        )
+    }
+
+    ///
+    /// Create a `Logger` with the default options for the specified
+    /// destination.
+    ///
+    /// - Arguments:
+    ///   - `log_file`: The complete path of the file to use for logging.
+    ///   - `destination`: The open destination of the logged lines.
+    ///   - `terminate_on_error`: If true then terminate the application when
+    ///     an error message is logged.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `Logger` object.
+    ///
+    fn with_destination(
+        log_file: String,
+        destination: Destination,
+        terminate_on_error: bool,
+    ) -> Self {
+        Self {
+            log_file,
+            destination: Shared(Arc::new(Mutex::new(destination))),
+            terminate_on_error: Arc::new(AtomicBool::new(terminate_on_error)),
+            context: Vec::new(),
+            counters: Arc::new(LevelCounters::default()),
+            timestamp_format: TimestampFormat::None,
+            timestamp_levels: [true; 4],
+            #[cfg(feature = "timezone")]
+            timezone: None,
+            clock: Arc::new(SystemClock),
+            start: Instant::now(),
+            filter: None,
+            redactor: None,
+            flush_policy: FlushPolicy::EveryLine,
+            create_dirs: false,
+            lazy: false,
+            line_ending: LineEnding::Lf,
+            control_chars: ControlChars::Verbatim,
+            escape_html: false,
+            include_pid: false,
+            include_module_path: false,
+            hostname: None,
+            tag: None,
+            suspended: Arc::new(AtomicBool::new(false)),
+            verbose_severity: Arc::new(AtomicU8::new(NO_OVERRIDE)),
+            max_message_len: None,
+            min_level: LogLevel::Debug,
+            header: None,
+            format: LogFormat::Plain,
+            console_symbols: None,
+            console_colors: false,
+            level_label: LevelLabel::Full,
+            level_labels: HashMap::new(),
+            last_record: None,
+            on_terminate: None,
+            continuation_prefix: None,
+            format_template: None,
+            console_format: None,
+            console: None,
+            flush_console: true,
+            max_file_size: None,
+            max_backups: 5,
+            sample_rates: [1; 4],
+            sample_counters: Arc::new(LevelCounters::default()),
+            rotation_period: None,
+            write_bom: false,
+            field_separator: ' ',
+            quote_values: false,
+            line_termination: LineTermination::Terminated,
+            error_exit_code: 1,
+            write_failure_policy: WriteFailurePolicy::Panic,
+            output_counters: Arc::new(OutputCounters::default()),
+            sequence_width: None,
+            sequence: Arc::new(AtomicU64::new(0)),
+            include_delta: false,
+            last_instant: Arc::new(Mutex::new(None)),
+            #[cfg(any(test, feature = "test-util"))]
+            issues: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    ///
+    /// Get the path of the log file.
+    ///
+    /// - Returns:
+    ///   - The complete path of the log file, or an empty string if the
+    ///     `Logger` writes to an arbitrary writer.
+    ///
+    pub fn log_file(&self) -> &str {
+        &self.log_file
+    }
+
+    ///
+    /// Check whether the application is terminated when an error message
+    /// is logged.
+    ///
+    /// - Returns:
+    ///   - True if error messages terminate the application.
+    ///
+    pub fn terminate_on_error(&self) -> bool {
+        self.terminate_on_error.load(Ordering::Relaxed)
+    }
+
+    ///
+    /// Prevent error messages from terminating the application until the
+    /// returned guard is dropped, e.g. while logging errors during a
+    /// shutdown or recovery routine. The previous behavior is restored
+    /// when the guard is dropped. The suppression applies to all the
+    /// clones of this `Logger`.
+    ///
+    /// - Returns:
+    ///   - The guard restoring the previous behavior on drop.
+    ///
+    pub fn suppress_terminate(&self) -> TerminateGuard<'_> {
+        TerminateGuard {
+            logger: self,
+            previous: self.terminate_on_error.swap(false, Ordering::Relaxed),
+        }
+    }
+
+    ///
+    /// Enter a nested scope until the returned guard is dropped. While it
+    /// is alive, the messages logged by the current thread are indented by
+    /// two spaces per enclosing scope, so that nested operations are
+    /// visually offset in the log. The nesting depth is tracked per thread
+    /// and shared by all the loggers.
+    ///
+    /// - Returns:
+    ///   - The guard leaving the scope on drop.
+    ///
+    pub fn enter_scope(&self) -> ScopeGuard {
+        SCOPE_DEPTH.with(|depth| depth.set(depth.get() + 1));
+
+        ScopeGuard {
+            _thread: std::marker::PhantomData,
+        }
+    }
+
+    ///
+    /// Set the least severe level of the messages which are logged.
+    /// Messages of a less severe level are dropped.
+    ///
+    /// - Arguments:
+    ///   - `min_level`: The minimum level to log.
+    ///
+    pub fn set_min_level(&mut self, min_level: LogLevel) {
+        self.min_level = min_level;
+    }
+
+    ///
+    /// Run a closure with the minimum level temporarily set to another
+    /// level, e.g. `Debug` for the targeted debugging of one operation.
+    /// The previous minimum level is restored when the closure returns,
+    /// even if it panics. The override applies to all the clones of this
+    /// `Logger`, including those used by other threads.
+    ///
+    /// - Arguments:
+    ///   - `level`: The minimum level while the closure runs.
+    ///   - `f`: The closure to run.
+    ///
+    /// - Returns:
+    ///   - The value returned by the closure.
+    ///
+    pub fn with_verbose<R>(&self, level: LogLevel, f: impl FnOnce() -> R) -> R {
+        let _guard = VerboseGuard {
+            logger: self,
+            previous: self
+                .verbose_severity
+                .swap(level.severity(), Ordering::Relaxed),
+        };
+
+        f()
+    }
+
+    ///
+    /// Get the severity of the least severe level which is logged, taking
+    /// into account any override by `with_verbose`.
+    ///
+    /// - Returns:
+    ///   - The severity of the effective minimum level.
+    ///
+    fn min_severity(&self) -> u8 {
+        match self.verbose_severity.load(Ordering::Relaxed) {
+            NO_OVERRIDE => self.min_level.severity(),
+            severity => severity,
+        }
+    }
+
+    ///
+    /// Set the format of the timestamp prefixed to each logged message.
+    ///
+    /// - Arguments:
+    ///   - `timestamp_format`: The timestamp format to use.
+    ///
+    pub fn set_timestamp_format(&mut self, timestamp_format: TimestampFormat) {
+        self.timestamp_format = timestamp_format;
+    }
+
+    ///
+    /// Restrict the timestamps to the records of some levels, e.g. only
+    /// `Warning` and `Error`, to reduce the noise of high-volume debug
+    /// output. The timestamps are still rendered in the timestamp format,
+    /// so they only appear if it is not `TimestampFormat::None`.
+    ///
+    /// - Arguments:
+    ///   - `levels`: The levels whose records carry a timestamp; all the
+    ///     levels by default.
+    ///
+    pub fn set_timestamp_levels(&mut self, levels: &[LogLevel]) {
+        self.timestamp_levels = [false; 4];
+
+        for level in levels {
+            self.timestamp_levels[level.severity() as usize] = true;
+        }
+    }
+
+    ///
+    /// Set the timezone in which the wall-clock timestamps are rendered,
+    /// regardless of the timezone of the host, e.g. `America/New_York`.
+    /// The timestamps carry the UTC offset in effect at each instant.
+    ///
+    /// - Arguments:
+    ///   - `timezone`: The timezone to use, or `None` for UTC.
+    ///
+    #[cfg(feature = "timezone")]
+    pub fn set_timezone(&mut self, timezone: Option<chrono_tz::Tz>) {
+        self.timezone = timezone;
+    }
+
+    ///
+    /// Format a wall-clock time according to the timestamp format, in the
+    /// configured timezone.
+    ///
+    /// - Arguments:
+    ///   - `time`: The time to format.
+    ///
+    /// - Returns:
+    ///   - The formatted time, or `None` if the format is not a wall-clock
+    ///     format.
+    ///
+    fn format_time(&self, time: SystemTime) -> Option<String> {
+        #[cfg(feature = "timezone")]
+        if let Some(timezone) = self.timezone {
+            return self.timestamp_format.format_time_in(time, timezone);
+        }
+
+        self.timestamp_format.format_time(time)
+    }
+
+    ///
+    /// Set the clock used for timestamping the logged messages.
+    ///
+    /// - Arguments:
+    ///   - `clock`: The clock to use instead of the system clock.
+    ///
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.clock = Arc::new(clock);
+    }
+
+    ///
+    /// Set a filter which decides whether a record is logged. Records for
+    /// which the filter returns `false` are dropped before any formatting
+    /// or I/O takes place.
+    ///
+    /// - Arguments:
+    ///   - `filter`: The filter to apply to the level and message of each record.
+    ///
+    pub fn set_filter(&mut self, filter: Box<LogFilter>) {
+        self.filter = Some(Shared(Arc::from(filter)));
+    }
+
+    ///
+    /// Sample the records of a level, keeping only the first of every
+    /// `rate` records, e.g. a rate of 100 on `Debug` writes one debug
+    /// record in a hundred. The sampling is deterministic and counts the
+    /// records of the level across all the clones of this `Logger`.
+    ///
+    /// - Arguments:
+    ///   - `level`: The level to sample.
+    ///   - `rate`: The sampling rate, where 0 and 1 keep every record.
+    ///
+    pub fn set_sample_rate(&mut self, level: LogLevel, rate: u64) {
+        self.sample_rates[level.severity() as usize] = rate.max(1);
+    }
+
+    ///
+    /// Set a redactor which masks sensitive parts of each message before
+    /// it is written to the screen and the log file.
+    ///
+    /// - Arguments:
+    ///   - `redactor`: The function which returns the redacted form of a message.
+    ///
+    pub fn set_redactor(&mut self, redactor: impl Fn(&str) -> String + Send + Sync + 'static) {
+        self.redactor = Some(Shared(Arc::new(redactor)));
+    }
+
+    ///
+    /// Set the policy deciding when the buffered records are written to the
+    /// log destination.
+    ///
+    /// - Arguments:
+    ///   - `flush_policy`: The flush policy to use.
+    ///
+    pub fn set_flush_policy(&mut self, flush_policy: FlushPolicy) {
+        self.flush_policy = flush_policy;
+    }
+
+    ///
+    /// Enable or disable the durable mode, i.e. the `FlushPolicy::OnError`
+    /// policy. Disabling it restores `FlushPolicy::EveryLine` if the
+    /// durable mode was enabled, and leaves any other policy unchanged.
+    ///
+    /// - Arguments:
+    ///   - `durable`: If true then synchronise the log file after errors.
+    ///
+    pub fn set_durable(&mut self, durable: bool) {
+        if durable {
+            self.flush_policy = FlushPolicy::OnError;
+        } else if self.flush_policy == FlushPolicy::OnError {
+            self.flush_policy = FlushPolicy::EveryLine;
+        }
+    }
+
+    ///
+    /// Set the format of the logged lines.
+    ///
+    /// - Arguments:
+    ///   - `format`: The format to use.
+    ///
+    pub fn set_format(&mut self, format: LogFormat) {
+        self.format = format;
+    }
+
+    ///
+    /// Set the format of the console lines independently of the format of
+    /// the lines written to the log destination, e.g. JSON on the console
+    /// for a log collector and plain text in the file for humans.
+    ///
+    /// - Arguments:
+    ///   - `console_format`: The format of the console lines, or `None` to
+    ///     use the format of the log destination.
+    ///
+    pub fn set_console_format(&mut self, console_format: Option<LogFormat>) {
+        self.console_format = console_format;
+    }
+
+    ///
+    /// Write the console lines to a writer instead of the standard output.
+    ///
+    /// - Arguments:
+    ///   - `writer`: The writer receiving the console lines.
+    ///
+    pub fn set_console_writer<W: Write + Send + 'static>(&mut self, writer: W) {
+        self.console = Some(Shared(Arc::new(Mutex::new(Box::new(writer)))));
+    }
+
+    ///
+    /// Flush the console after each line, so that the lines appear
+    /// promptly even when the output is piped or redirected. It is enabled
+    /// by default; disabling it may improve the throughput.
+    ///
+    /// - Arguments:
+    ///   - `flush_console`: If true then flush the console after each line.
+    ///
+    pub fn set_flush_console(&mut self, flush_console: bool) {
+        self.flush_console = flush_console;
+    }
+
+    ///
+    /// Set the symbols prefixed to the console lines of each level, such
+    /// as `LevelSymbols::default()`. The symbols never appear in the log file.
+    ///
+    /// - Arguments:
+    ///   - `symbols`: The symbols to use, or `None` for no symbols.
+    ///
+    pub fn set_console_symbols(&mut self, symbols: Option<LevelSymbols>) {
+        self.console_symbols = symbols;
+    }
+
+    ///
+    /// Enable or disable the coloring of the console lines according to
+    /// their level. The colors never appear in the log file.
+    ///
+    /// - Arguments:
+    ///   - `console_colors`: If true then color the console lines.
+    ///
+    pub fn set_console_colors(&mut self, console_colors: bool) {
+        self.console_colors = console_colors;
+    }
+
+    ///
+    /// Set the label used for rendering the level of each logged line,
+    /// e.g. `LevelLabel::Short` to render `Warning` as `WARN`.
+    ///
+    /// - Arguments:
+    ///   - `level_label`: The level label to use.
+    ///
+    pub fn set_level_label(&mut self, level_label: LevelLabel) {
+        self.level_label = level_label;
+    }
+
+    ///
+    /// Override the labels of specific levels, e.g. to localise them or
+    /// use custom glyphs. The levels without an override are rendered
+    /// according to the level label.
+    ///
+    /// - Arguments:
+    ///   - `level_labels`: The labels of the overridden levels.
+    ///
+    pub fn set_level_labels(&mut self, level_labels: HashMap<LogLevel, String>) {
+        self.level_labels = level_labels;
+    }
+
+    ///
+    /// Render the label of a level, applying its override, if any.
+    ///
+    /// - Arguments:
+    ///   - `level`: The level to render.
+    ///
+    /// - Returns:
+    ///   - The rendered label of the level.
+    ///
+    fn render_level(&self, level: LogLevel) -> String {
+        match self.level_labels.get(&level) {
+            Some(label) => label.clone(),
+            None => self.level_label.render(level),
+        }
+    }
+
+    ///
+    /// Set the line ending appended to each line written to the log file.
+    ///
+    /// - Arguments:
+    ///   - `line_ending`: The line ending to use.
+    ///
+    pub fn set_line_ending(&mut self, line_ending: LineEnding) {
+        self.line_ending = line_ending;
+    }
+
+    ///
+    /// Set the placement of the line endings between the records. With
+    /// `LineTermination::Separated` the line ending is written before each
+    /// record except the first, so the log never ends with a line ending,
+    /// which is convenient when it is concatenated or embedded. The binary
+    /// format is unaffected.
+    ///
+    /// - Arguments:
+    ///   - `line_termination`: The placement of the line endings.
+    ///
+    pub fn set_line_termination(&mut self, line_termination: LineTermination) {
+        self.line_termination = line_termination;
+    }
+
+    ///
+    /// Set the handling of the control characters contained in each
+    /// message. Escaping them prevents raw sequences, such as ANSI escapes
+    /// or carriage returns, from corrupting the terminal or the log parsers.
+    ///
+    /// - Arguments:
+    ///   - `control_chars`: The control character handling to use.
+    ///
+    pub fn set_control_chars(&mut self, control_chars: ControlChars) {
+        self.control_chars = control_chars;
+    }
+
+    ///
+    /// Escape the HTML special characters of each message, e.g. `<` as
+    /// `&lt;`, so that the log can be served safely by a web viewer. Only
+    /// the message body is escaped, after any truncation.
+    ///
+    /// - Arguments:
+    ///   - `escape_html`: If true then escape the messages.
+    ///
+    pub fn set_escape_html(&mut self, escape_html: bool) {
+        self.escape_html = escape_html;
+    }
+
+    ///
+    /// Include the id of the current process in each logged line as a
+    /// `pid=1234` field.
+    ///
+    /// - Arguments:
+    ///   - `include_pid`: If true then include the process id.
+    ///
+    pub fn set_include_pid(&mut self, include_pid: bool) {
+        self.include_pid = include_pid;
+    }
+
+    ///
+    /// Include the module path of the caller in each line logged through
+    /// the logging macros, e.g. `[myapp::db] [INFO] connected`, so that the
+    /// records can be attributed to modules. In the JSON format it is
+    /// written as a `module` key. Records logged through the methods carry
+    /// no module path, since a function cannot see the module of its caller.
+    ///
+    /// - Arguments:
+    ///   - `include_module_path`: If true then include the module path.
+    ///
+    pub fn set_include_module_path(&mut self, include_module_path: bool) {
+        self.include_module_path = include_module_path;
+    }
+
+    ///
+    /// Include the hostname of the machine in each logged line as a
+    /// `host=name` field. The hostname is resolved once, when enabled.
+    ///
+    /// - Arguments:
+    ///   - `include_hostname`: If true then include the hostname.
+    ///
+    pub fn set_include_hostname(&mut self, include_hostname: bool) {
+        self.hostname = include_hostname.then(hostname);
+    }
+
+    ///
+    /// Set a static tag, e.g. `db` or `http`, which is inserted between the
+    /// level and the message of each line as `[db]`, so that the lines of
+    /// several subsystems sharing a file are attributable. In the JSON
+    /// format it is written as a `tag` key.
+    ///
+    /// - Arguments:
+    ///   - `tag`: The tag to use, or `None` for no tag.
+    ///
+    pub fn set_tag(&mut self, tag: Option<&str>) {
+        self.tag = tag.map(str::to_string);
+    }
+
+    ///
+    /// Limit the number of characters of each message. Longer messages
+    /// are truncated at a character boundary and marked with
+    /// `TRUNCATION_MARKER`. The timestamp, level and fields of the line do
+    /// not count towards the limit.
+    ///
+    /// - Arguments:
+    ///   - `max_message_len`: The maximum number of characters, or `None`
+    ///     for no limit.
+    ///
+    pub fn set_max_message_len(&mut self, max_message_len: Option<usize>) {
+        self.max_message_len = max_message_len;
+    }
+
+    ///
+    /// Enable or disable the sequence numbers of the records, e.g.
+    /// `[#0001] [INFO] message`, which reveal dropped or reordered lines in
+    /// a pipeline. The numbers start from 1 and are shared by all the
+    /// clones of this `Logger`. In the JSON format they are written as a
+    /// `seq` member, while the binary format does not include them.
+    ///
+    /// - Arguments:
+    ///   - `sequence_width`: The minimum number of digits, padded with
+    ///     zeros, or `None` to disable the sequence numbers.
+    ///
+    pub fn set_sequence_width(&mut self, sequence_width: Option<usize>) {
+        self.sequence_width = sequence_width;
+    }
+
+    ///
+    /// Include in each record the time elapsed since the previous record
+    /// of this `Logger` or its clones, e.g. `[+4.2ms] [INFO] message`, for
+    /// quick profiling of the gaps between events. The first record shows
+    /// a delta of zero. In the JSON format it is written as a `delta_ms`
+    /// member.
+    ///
+    /// - Arguments:
+    ///   - `include_delta`: If true then include the deltas.
+    ///
+    pub fn set_include_delta(&mut self, include_delta: bool) {
+        self.include_delta = include_delta;
+    }
+
+    ///
+    /// Assign the sequence number and the delta of a record about to be
+    /// written, if enabled.
+    ///
+    /// - Arguments:
+    ///   - `record`: The record to stamp.
+    ///
+    fn stamp_record(&self, record: &mut Record) {
+        if self.sequence_width.is_some() {
+            record.sequence = Some(self.sequence.fetch_add(1, Ordering::Relaxed) + 1);
+        }
+
+        if self.include_delta {
+            let now = Instant::now();
+            let mut last_instant = self
+                .last_instant
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+
+            record.delta = Some(
+                last_instant
+                    .replace(now)
+                    .map(|last| now.saturating_duration_since(last))
+                    .unwrap_or_default(),
+            );
+        }
+    }
+
+    ///
+    /// Set the separator written before each key-value field in the plain
+    /// formats, e.g. a tab instead of the default space.
+    ///
+    /// - Arguments:
+    ///   - `field_separator`: The separator of the fields.
+    ///
+    pub fn set_field_separator(&mut self, field_separator: char) {
+        self.field_separator = field_separator;
+    }
+
+    ///
+    /// Enable or disable the logfmt-compatible quoting of the field values
+    /// in the plain formats. When enabled, values which are empty or
+    /// contain whitespace, an equals sign, a quote or a control character
+    /// are wrapped in quotes, with the quotes, the backslashes and the
+    /// control characters escaped.
+    ///
+    /// - Arguments:
+    ///   - `quote_values`: If true then quote the field values.
+    ///
+    pub fn set_quote_values(&mut self, quote_values: bool) {
+        self.quote_values = quote_values;
+    }
+
+    ///
+    /// Set the prefix of the continuation lines of multiline messages, for
+    /// example `"    | "`, so that they are not mistaken for separate
+    /// records. By default continuation lines are written verbatim. The
+    /// prefix does not apply to the JSON format, where newlines are always
+    /// escaped.
+    ///
+    /// - Arguments:
+    ///   - `continuation_prefix`: The prefix of each line after the first,
+    ///     or `None` to write continuation lines verbatim.
+    ///
+    pub fn set_continuation_prefix(&mut self, continuation_prefix: Option<String>) {
+        self.continuation_prefix = continuation_prefix;
+    }
+
+    ///
+    /// Set the template of the lines written in the plain format, such as
+    /// `{timestamp} {level}: {message}{fields}`, replacing the fixed layout
+    /// of the plain format. The placeholders are those of
+    /// `TEMPLATE_PLACEHOLDERS`; an absent part, e.g. `{tag}` without a tag,
+    /// expands to nothing. The template does not apply to the other
+    /// formats.
+    ///
+    /// - Arguments:
+    ///   - `format_template`: The template of the plain lines, or `None`
+    ///     to use the fixed layout.
+    ///
+    /// - Returns:
+    ///   - Success, or `LoggerError::InvalidTemplate` listing the unknown
+    ///     placeholders, in which case the template is left unchanged.
+    ///
+    pub fn set_format_template(
+        &mut self,
+        format_template: Option<&str>,
+    ) -> Result<(), LoggerError> {
+        if let Some(template) = format_template {
+            validate_template(template)?;
+        }
+
+        self.format_template = format_template.map(str::to_string);
+        Ok(())
+    }
+
+    ///
+    /// Set a callback producing a header line, such as
+    /// `# log started pid=1234`, which is written as the first line of each
+    /// newly created log file. If the current log file is still empty, the
+    /// header is written to it immediately.
+    ///
+    /// - Arguments:
+    ///   - `header`: The function which returns the header line.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    pub fn set_header(
+        &mut self,
+        header: impl Fn() -> String + Send + Sync + 'static,
+    ) -> Result<(), std::io::Error> {
+        self.header = Some(Shared(Arc::new(header)));
+
+        let mut destination = self.lock_destination();
+
+        if let Target::File(file) = &mut destination.target {
+            let len = file.metadata()?.len();
+
+            if len == 0 || (self.write_bom && len == UTF8_BOM.len() as u64) {
+                self.write_header(file)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Enable or disable the UTF-8 byte order mark, which some Windows log
+    /// viewers expect, at the start of each newly created log file. If the
+    /// current log file is still empty, the mark is written to it
+    /// immediately, so enable it before setting a header. It is never
+    /// written in the binary format.
+    ///
+    /// - Arguments:
+    ///   - `write_bom`: If true then write the byte order mark.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    pub fn set_write_bom(&mut self, write_bom: bool) -> Result<(), std::io::Error> {
+        self.write_bom = write_bom;
+
+        let mut destination = self.lock_destination();
+
+        if let Target::File(file) = &mut destination.target {
+            if file.metadata()?.len() == 0 {
+                self.write_bom(file)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Switch the logging to a different file. The current destination is
+    /// flushed and released, and the new file is created if it does not
+    /// exist or appended to if it does. Existing clones of this `Logger`
+    /// keep logging to the previous destination.
+    ///
+    /// - Arguments:
+    ///   - `path`: The complete path of the new log file.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    pub fn set_log_file(&mut self, path: impl AsRef<Path>) -> Result<(), std::io::Error> {
+        let path = path.as_ref();
+        let mut file = OpenOptions::new().append(true).create(true).open(path)?;
+
+        if file.metadata()?.len() == 0 {
+            self.write_preamble(&mut file)?;
+        }
+
+        let mut destination = Destination::new(Target::File(file));
+        destination.needs_separator = self.ends_with_partial_line(path)?;
+
+        self.lock_destination().flush()?;
+        self.destination = Shared(Arc::new(Mutex::new(destination)));
+        self.log_file = path.to_string_lossy().into_owned();
+
+        Ok(())
+    }
+
+    ///
+    /// Rotate the log file automatically once its size reaches a limit.
+    /// The check takes place after each write, so the rotated file may
+    /// exceed the limit by the last written lines.
+    ///
+    /// - Arguments:
+    ///   - `max_file_size`: The size limit in bytes, or `None` to disable
+    ///     the automatic rotation.
+    ///
+    pub fn set_max_file_size(&mut self, max_file_size: Option<u64>) {
+        self.max_file_size = max_file_size;
+    }
+
+    ///
+    /// Set the maximum number of numbered backups kept by the rotation,
+    /// `app.log.1` being the newest. Older backups are deleted. The
+    /// default is 5.
+    ///
+    /// - Arguments:
+    ///   - `max_backups`: The number of backups to keep, where zero
+    ///     discards the rotated contents.
+    ///
+    pub fn set_max_backups(&mut self, max_backups: usize) {
+        self.max_backups = max_backups;
+    }
+
+    ///
+    /// Switch to a new log file every period. The name of each file
+    /// carries the label of its period before the extension, e.g.
+    /// `app-2025-01-02-13.log` for hourly and `app-2025-W01.log` for weekly
+    /// rotation, using the clock of this `Logger` in UTC. The switch is
+    /// detected on each write, so the configured path itself is no longer
+    /// written to.
+    ///
+    /// - Arguments:
+    ///   - `rotation_period`: The rotation period, or `None` to log to the
+    ///     configured path.
+    ///
+    pub fn set_rotation_period(&mut self, rotation_period: Option<RotationPeriod>) {
+        self.rotation_period = rotation_period;
+    }
+
+    ///
+    /// Get the size of the active log file. Records which are buffered
+    /// and not yet written are not included.
+    ///
+    /// - Returns:
+    ///   - The size of the log file in bytes. Writer destinations have no
+    ///     size and return `ErrorKind::Unsupported`.
+    ///
+    pub fn current_size(&self) -> Result<u64, std::io::Error> {
+        file_size(&self.lock_destination())?.ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "Logger: a writer destination has no size.",
+            )
+        })
+    }
+
+    ///
+    /// Rotate the log file: the buffered lines are written, the active file
+    /// becomes the backup `.1`, the existing backups are shifted and pruned,
+    /// and logging continues in a new file.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure. Writer destinations
+    ///     cannot be rotated and return `ErrorKind::Unsupported`.
+    ///
+    pub fn rotate(&self) -> Result<(), std::io::Error> {
+        self.rotate_destination(&mut self.lock_destination())
+    }
+
+    ///
+    /// Rotate the log file of a locked destination.
+    ///
+    /// - Arguments:
+    ///   - `destination`: The locked destination.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    fn rotate_destination(&self, destination: &mut Destination) -> Result<(), std::io::Error> {
+        if let Target::Lazy(_) | Target::Spool(_) = destination.target {
+            return Ok(());
+        }
+
+        if let Target::Writer(_) = destination.target {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "Logger: a writer destination cannot be rotated.",
+            ));
+        }
+
+        destination.flush()?;
+
+        let path = self.active_path(destination);
+        rotate_backups(&path, self.max_backups)?;
+
+        let mut file = OpenOptions::new().append(true).create(true).open(&path)?;
+        self.write_preamble(&mut file)?;
+        destination.target = Target::File(file);
+        destination.needs_separator = false;
+
+        Ok(())
+    }
+
+    ///
+    /// Switch a locked destination to the log file of the current rotation
+    /// period, if the period has changed since the last write.
+    ///
+    /// - Arguments:
+    ///   - `destination`: The locked destination.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    fn switch_period(&self, destination: &mut Destination) -> Result<(), std::io::Error> {
+        let Some(rotation_period) = self.rotation_period else {
+            return Ok(());
+        };
+
+        if let Target::Writer(_) | Target::Spool(_) = destination.target {
+            return Ok(());
+        }
+
+        let label = rotation_period.label(self.clock.now());
+
+        if destination.period.as_deref() == Some(label.as_str()) {
+            return Ok(());
+        }
+
+        destination.flush()?;
+
+        let path = period_path(Path::new(&self.log_file), &label);
+        let mut file = OpenOptions::new().append(true).create(true).open(&path)?;
+
+        if file.metadata()?.len() == 0 {
+            self.write_preamble(&mut file)?;
+        }
+
+        destination.target = Target::File(file);
+        destination.period = Some(label);
+        destination.needs_separator = self.ends_with_partial_line(&path)?;
+
+        Ok(())
+    }
+
+    ///
+    /// Get the path of the log file open in a locked destination.
+    ///
+    /// - Arguments:
+    ///   - `destination`: The locked destination.
+    ///
+    /// - Returns:
+    ///   - The configured path, or the path of the current rotation period.
+    ///
+    fn active_path(&self, destination: &Destination) -> PathBuf {
+        match &destination.period {
+            Some(label) => period_path(Path::new(&self.log_file), label),
+            None => PathBuf::from(&self.log_file),
+        }
+    }
+
+    ///
+    /// Format a message exactly as it would be written by `log`, without
+    /// performing any I/O.
+    ///
+    /// - Arguments:
+    ///   - `level`: The level of the message.
+    ///   - `message`: The message to format.
+    ///
+    /// - Returns:
+    ///   - The formatted line, excluding the line terminator.
+    ///
+    pub fn format_line(&self, level: LogLevel, message: &str) -> String {
+        self.format_record(level, message, &[])
+    }
+
+    ///
+    /// Format a message exactly as it would be printed on the console by
+    /// `log`, including the level symbol and color, if enabled.
+    ///
+    /// - Arguments:
+    ///   - `level`: The level of the message.
+    ///   - `message`: The message to format.
+    ///
+    /// - Returns:
+    ///   - The formatted console line, excluding the line terminator.
+    ///
+    pub fn format_console_line(&self, level: LogLevel, message: &str) -> String {
+        let record = self.prepare_record(None, level, message, &[]);
+        self.decorate_console_line(level, &self.render_record(self.console_format(), &record))
+    }
+
+    ///
+    /// Get the format of the console lines.
+    ///
+    /// - Returns:
+    ///   - The console format, falling back to the format of the log
+    ///     destination.
+    ///
+    fn console_format(&self) -> LogFormat {
+        self.console_format.unwrap_or(self.format)
+    }
+
+    ///
+    /// Decorate a formatted line with the console symbol and color of its
+    /// level, if enabled.
+    ///
+    /// - Arguments:
+    ///   - `level`: The level of the message.
+    ///   - `line`: The formatted line.
+    ///
+    /// - Returns:
+    ///   - The decorated console line.
+    ///
+    fn decorate_console_line(&self, level: LogLevel, line: &str) -> String {
+        let line = match &self.console_symbols {
+            Some(symbols) => format!("{} {}", symbols.symbol(level), line),
+            None => line.to_string(),
+        };
+
+        if self.console_colors {
+            format!("{}{}{}", level_color(level), line, COLOR_RESET)
+        } else {
+            line
+        }
+    }
+
+    ///
+    /// Format a record with typed key-value fields.
+    ///
+    /// - Arguments:
+    ///   - `level`: The level of the message.
+    ///   - `message`: The message to format.
+    ///   - `fields`: The key-value fields of the record.
+    ///
+    /// - Returns:
+    ///   - The formatted line, excluding the line terminator.
+    ///
+    fn format_record(
+        &self,
+        level: LogLevel,
+        message: &str,
+        fields: &[(&str, &dyn Display)],
+    ) -> String {
+        self.render_record(
+            self.format,
+            &self.prepare_record(None, level, message, fields),
+        )
+    }
+
+    ///
+    /// Prepare a record for rendering by processing its message and
+    /// collecting its fields.
+    ///
+    /// - Arguments:
+    ///   - `time`: The time of the record, or `None` for the current time.
+    ///   - `level`: The level of the message.
+    ///   - `message`: The message of the record.
+    ///   - `fields`: The key-value fields of the record.
+    ///
+    /// - Returns:
+    ///   - The prepared record.
+    ///
+    fn prepare_record<'a>(
+        &'a self,
+        time: Option<SystemTime>,
+        level: LogLevel,
+        message: &str,
+        fields: &[(&'a str, &dyn Display)],
+    ) -> Record<'a> {
+        let message = match &self.redactor {
+            Some(redactor) => (redactor.0)(message),
+            None => message.to_string(),
+        };
+        let mut message = self.control_chars.apply(&message);
+
+        if let Some(max_len) = self.max_message_len {
+            message = truncate_message(&message, max_len);
+        }
+
+        if self.escape_html {
+            message = escape_html(&message);
+        }
+        //
+        // Offset the messages of nested scopes.
+        //
+        let depth = SCOPE_DEPTH.with(Cell::get);
+
+        if depth > 0 {
+            message = format!("{}{}", SCOPE_INDENT.repeat(depth), message);
+        }
+        //
+        // Collect the fields of the record in their output order.
+        //
+        let mut all_fields: Vec<(&str, String)> = Vec::new();
+
+        if let Some(hostname) = &self.hostname {
+            all_fields.push(("host", hostname.clone()));
+        }
+
+        if self.include_pid {
+            all_fields.push(("pid", std::process::id().to_string()));
+        }
+
+        for (key, value) in &self.context {
+            all_fields.push((key, value.clone()));
+        }
+
+        for (key, value) in fields {
+            all_fields.push((key, value.to_string()));
+        }
+
+        Record {
+            level,
+            time: time.unwrap_or_else(|| self.clock.now()),
+            uptime: self.start.elapsed(),
+            message,
+            fields: all_fields,
+            sequence: None,
+            module: None,
+            delta: None,
+        }
+    }
+
+    ///
+    /// Render the key-value fields of a record in the plain formats, each
+    /// preceded by the field separator.
+    ///
+    /// - Arguments:
+    ///   - `record`: The record whose fields are rendered.
+    ///
+    /// - Returns:
+    ///   - The rendered fields.
+    ///
+    fn render_fields(&self, record: &Record) -> String {
+        let mut rendered = String::new();
+
+        for (key, value) in &record.fields {
+            let value = if self.quote_values {
+                quote_value(value)
+            } else {
+                value.clone()
+            };
+
+            rendered.push_str(&format!("{}{}={}", self.field_separator, key, value));
+        }
+
+        rendered
+    }
+
+    ///
+    /// Render a prepared record in the plain format with the format
+    /// template.
+    ///
+    /// - Arguments:
+    ///   - `record`: The record to render.
+    ///   - `timestamped`: If true then the level of the record is
+    ///     timestamped.
+    ///
+    /// - Returns:
+    ///   - The rendered line, excluding the line terminator.
+    ///
+    fn render_template(&self, record: &Record, timestamped: bool) -> String {
+        let template = self.format_template.as_deref().unwrap_or_default();
+
+        expand_template(template, |name| match name {
+            "seq" => record
+                .sequence
+                .map(|sequence| {
+                    format!(
+                        "{:0width$}",
+                        sequence,
+                        width = self.sequence_width.unwrap_or_default()
+                    )
+                })
+                .unwrap_or_default(),
+            "timestamp" => self
+                .format_time(record.time)
+                .filter(|_| timestamped)
+                .unwrap_or_default(),
+            "uptime" => format_uptime(record.uptime),
+            "delta" => record.delta.map(format_delta).unwrap_or_default(),
+            "module" => record.module.unwrap_or_default().to_string(),
+            "level" => self.render_level(record.level),
+            "tag" => self.tag.clone().unwrap_or_default(),
+            "message" => match &self.continuation_prefix {
+                Some(prefix) => record.message.replace('\n', &format!("\n{}", prefix)),
+                None => record.message.clone(),
+            },
+            "fields" => self.render_fields(record),
+            _ => String::new(),
+        })
+    }
+
+    ///
+    /// Render a prepared record in a specific format.
+    ///
+    /// - Arguments:
+    ///   - `format`: The format of the line.
+    ///   - `record`: The record to render.
+    ///
+    /// - Returns:
+    ///   - The rendered line, excluding the line terminator.
+    ///
+    fn render_record(&self, format: LogFormat, record: &Record) -> String {
+        let level = record.level;
+        let timestamped = self.timestamp_levels[level.severity() as usize];
+
+        match format {
+            LogFormat::Plain if self.format_template.is_some() => {
+                self.render_template(record, timestamped)
+            }
+            LogFormat::Plain | LogFormat::Compact | LogFormat::Binary => {
+                let mut line = match record.sequence {
+                    Some(sequence) => format!(
+                        "[#{:0width$}] ",
+                        sequence,
+                        width = self.sequence_width.unwrap_or_default()
+                    ),
+                    None => String::new(),
+                };
+
+                match self.format_time(record.time).filter(|_| timestamped) {
+                    Some(timestamp) => line.push_str(&format!("{} ", timestamp)),
+                    None if timestamped
+                        && self.timestamp_format == TimestampFormat::UptimeSeconds =>
+                    {
+                        line.push_str(&format!("{} ", format_uptime(record.uptime)))
+                    }
+                    None => (),
+                }
+
+                if let Some(delta) = record.delta {
+                    line.push_str(&format!("{} ", format_delta(delta)));
+                }
+
+                if let Some(module) = record.module {
+                    line.push_str(&format!("[{}] ", module));
+                }
+
+                let mut message = record.message.clone();
+
+                if let Some(prefix) = &self.continuation_prefix {
+                    message = message.replace('\n', &format!("\n{}", prefix));
+                }
+
+                if format == LogFormat::Compact {
+                    line.push_str(&format!("{} ", level.to_level_char()));
+                } else {
+                    line.push_str(&format!("[{}] ", self.render_level(level)));
+                }
+
+                if let Some(tag) = &self.tag {
+                    line.push_str(&format!("[{}] ", tag));
+                }
+
+                line.push_str(&message);
+
+                line.push_str(&self.render_fields(record));
+                line
+            }
+            LogFormat::Json => {
+                let mut line = String::from("{");
+
+                if let Some(sequence) = record.sequence {
+                    line.push_str(&format!("\"seq\":{},", sequence));
+                }
+
+                match self.format_time(record.time).filter(|_| timestamped) {
+                    Some(timestamp) => line.push_str(&format!("\"timestamp\":\"{}\",", timestamp)),
+                    None if timestamped
+                        && self.timestamp_format == TimestampFormat::UptimeSeconds =>
+                    {
+                        line.push_str(&format!("\"uptime\":{:.3},", record.uptime.as_secs_f64()))
+                    }
+                    None => (),
+                }
+
+                if let Some(delta) = record.delta {
+                    line.push_str(&format!(
+                        "\"delta_ms\":{:.3},",
+                        delta.as_secs_f64() * 1_000.0
+                    ));
+                }
+
+                if let Some(module) = record.module {
+                    line.push_str(&format!("\"module\":\"{}\",", escape_json(module)));
+                }
+
+                line.push_str(&format!(
+                    "\"level\":\"{}\",",
+                    escape_json(&self.render_level(level))
+                ));
+
+                if let Some(tag) = &self.tag {
+                    line.push_str(&format!("\"tag\":\"{}\",", escape_json(tag)));
+                }
+
+                line.push_str(&format!("\"message\":\"{}\"", escape_json(&record.message)));
+
+                for (key, value) in &record.fields {
+                    line.push_str(&format!(
+                        ",\"{}\":\"{}\"",
+                        escape_json(key),
+                        escape_json(value)
+                    ));
+                }
+
+                line.push('}');
+                line
+            }
+        }
+    }
+
+    ///
+    /// Create a derived `Logger` which appends the specified key-value
+    /// fields to every logged message. The original `Logger` is unaffected.
+    ///
+    /// - Arguments:
+    ///   - `fields`: The key-value context fields to append.
+    ///
+    /// - Returns:
+    ///   - The derived `Logger` object.
+    ///
+    pub fn with_context(&self, fields: &[(&str, &str)]) -> Logger {
+        let mut logger = self.clone();
+
+        logger.context.extend(
+            fields
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string())),
+        );
+
+        logger
+    }
+
+    ///
+    /// Get the number of messages logged at each level since the creation
+    /// of this `Logger`.
+    ///
+    /// - Returns:
+    ///   - The per-level message counts.
+    ///
+    pub fn counts(&self) -> LevelCounts {
+        self.counters.snapshot()
+    }
+
+    ///
+    /// Get the effective configuration of this `Logger`, e.g. to dump it
+    /// for diagnostics or to save a modified configuration. The callbacks,
+    /// the open log file and the counters are not part of the
+    /// configuration.
+    ///
+    /// - Returns:
+    ///   - The configuration of this `Logger`.
+    ///
+    pub fn config(&self) -> LoggerConfig {
+        LoggerConfig {
+            path: self.log_file.clone(),
+            terminate_on_error: self.terminate_on_error(),
+            error_exit_code: self.error_exit_code,
+            create_dirs: self.create_dirs,
+            lazy: self.lazy,
+            level: self.min_level,
+            format: self.format,
+            console_format: self.console_format,
+            format_template: self.format_template.clone(),
+            level_label: self.level_label,
+            level_labels: self.level_labels.clone(),
+            timestamp_format: self.timestamp_format,
+            line_ending: self.line_ending,
+            line_termination: self.line_termination,
+            control_chars: self.control_chars,
+            include_pid: self.include_pid,
+            include_hostname: self.hostname.is_some(),
+            durable: false,
+            flush_policy: self.flush_policy,
+            max_message_len: self.max_message_len,
+            write_bom: self.write_bom,
+            max_file_size: self.max_file_size,
+            max_backups: self.max_backups,
+            rotation_period: self.rotation_period,
+        }
+    }
+
+    ///
+    /// Get a snapshot of the statistics of this `Logger`, including its
+    /// clones: the number of records, the bytes written to the log
+    /// destination, the per-level counts and the time of the last record.
+    ///
+    /// - Returns:
+    ///   - The current statistics.
+    ///
+    pub fn stats(&self) -> LoggerStats {
+        self.output_counters.snapshot(self.counters.snapshot())
+    }
+
+    ///
+    /// Check whether messages of a specific level would currently be
+    /// logged, taking into account whether logging is suspended and the
+    /// minimum level. It allows callers to skip building expensive messages
+    /// that would be discarded. The filter is not consulted, since it
+    /// decides per message.
+    ///
+    /// - Arguments:
+    ///   - `level`: The logging level to check.
+    ///
+    /// - Returns:
+    ///   - True if messages of this level are logged.
+    ///
+    pub fn enabled(&self, level: LogLevel) -> bool {
+        !self.suspended.load(Ordering::Relaxed) && level.severity() >= self.min_severity()
+    }
+
+    ///
+    /// Render a sample line for each level at or above the minimum level,
+    /// using the current format of the log destination, timestamps, tag
+    /// and fields, e.g. to preview the effect of the settings in a user
+    /// interface. Nothing is written and no colors are applied.
+    ///
+    /// - Returns:
+    ///   - The levels, from the least to the most severe, and their sample
+    ///     lines.
+    ///
+    pub fn preview(&self) -> Vec<(LogLevel, String)> {
+        LogLevel::all()
+            .iter()
+            .filter(|level| level.severity() >= self.min_severity())
+            .map(|&level| {
+                let record = self.prepare_record(None, level, "Sample message", &[]);
+                (level, self.render_record(self.format, &record))
+            })
+            .collect()
+    }
+
+    ///
+    /// Log a message.
+    ///
+    /// - Arguments:
+    ///   - `message`: The message to log.
+    ///   - `level`: The level of the message.
+    ///
+    pub fn log(&self, level: LogLevel, message: &str) -> Result<(), LoggerError> {
+        self.log_kv(level, message, &[])
+    }
+
+    ///
+    /// Log a message with typed key-value fields. The fields are rendered
+    /// as `key=value` pairs in plain format and as object members in JSON
+    /// format.
+    ///
+    /// - Arguments:
+    ///   - `level`: The level of the message.
+    ///   - `message`: The message to log.
+    ///   - `fields`: The key-value fields of the record.
+    ///
+    pub fn log_kv(
+        &self,
+        level: LogLevel,
+        message: &str,
+        fields: &[(&str, &dyn Display)],
+    ) -> Result<(), LoggerError> {
+        self.log_record(None, None, level, message, fields)
+    }
+
+    ///
+    /// Log a message on behalf of a module, which is used by the logging
+    /// macros to pass `module_path!()` of their caller.
+    ///
+    /// - Arguments:
+    ///   - `module`: The module path of the caller.
+    ///   - `level`: The level of the message.
+    ///   - `message`: The message to log.
+    ///
+    #[doc(hidden)]
+    pub fn log_module(
+        &self,
+        module: &'static str,
+        level: LogLevel,
+        message: &str,
+    ) -> Result<(), LoggerError> {
+        self.log_record(None, Some(module), level, message, &[])
+    }
+
+    ///
+    /// Write a message verbatim, without a timestamp, a level or any
+    /// fields, e.g. for banners, separators or pre-formatted records. The
+    /// message bypasses the minimum level, the filter, the sampling and the
+    /// processing of messages, but is dropped while logging is suspended.
+    /// It is not included in the per-level counts. In the binary format it
+    /// is written as an `Info` record.
+    ///
+    /// - Arguments:
+    ///   - `message`: The line to write, excluding the line terminator.
+    ///
+    pub fn log_raw(&self, message: &str) -> Result<(), LoggerError> {
+        if self.suspended.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let data = match self.format {
+            LogFormat::Binary => encode_record(LogLevel::Info, self.clock.now(), message),
+            _ => self.terminate_line(&strip_ansi(message)),
+        };
+
+        self.write_lines(LogLevel::Info, message, &data)?;
+
+        Ok(())
+    }
+
+    ///
+    /// Append the lines of another log file to the log destination, e.g.
+    /// to consolidate the logs of several runs or shards. The lines are
+    /// copied as they are, apart from their ANSI escape sequences, which
+    /// never reach the log destination, using the line ending of this
+    /// `Logger`, and are neither printed on the screen nor included in the
+    /// per-level counts.
+    ///
+    /// - Arguments:
+    ///   - `other_path`: The path of the log file to import.
+    ///
+    /// - Returns:
+    ///   - The number of lines imported, or the error which occurred. Text
+    ///     cannot be imported into a log in the binary format.
+    ///
+    pub fn import(&self, other_path: &Path) -> Result<usize, std::io::Error> {
+        if self.format == LogFormat::Binary {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "cannot import text into a binary log",
+            ));
+        }
+
+        let content = std::fs::read_to_string(other_path)?;
+        let lines: Vec<Cow<'_, str>> = content.lines().map(strip_ansi).collect();
+
+        if lines.is_empty() {
+            return Ok(0);
+        }
+
+        if self.is_spool() {
+            for line in &lines {
+                self.persist(LogLevel::Info, &self.terminate_line(line))?;
+            }
+        } else {
+            let data = self.terminate_line(&lines.join(self.line_ending.as_str()));
+            self.persist(LogLevel::Info, &data)?;
+        }
+
+        self.lock_destination().flush()?;
+
+        Ok(lines.len())
+    }
+
+    ///
+    /// Log an audit record, which is never dropped: it bypasses the
+    /// suspension, the minimum level, the filter, the sampling and the
+    /// collapsing of repeated records, and the log file is synchronised to
+    /// the disk before returning. The record is logged at the `Info` level
+    /// with an `audit=true` field.
+    ///
+    /// - Arguments:
+    ///   - `message`: The message to log.
+    ///
+    /// - Returns:
+    ///   - A result indicating whether the record was persisted.
+    ///
+    pub fn audit(&self, message: &str) -> Result<(), LoggerError> {
+        let record = self.prepare_record(None, LogLevel::Info, message, &[("audit", &true)]);
+
+        self.write_output(record)?;
+        self.lock_destination().sync()?;
+        self.counters.increment(LogLevel::Info);
+
+        Ok(())
+    }
+
+    ///
+    /// Log many records at once, writing them to the log destination with
+    /// a single write and flush instead of one per record. The suspension,
+    /// the minimum level and the filter apply to each record, while the
+    /// sampling and the collapsing of repeated records do not. The batch is
+    /// flushed according to its most severe record, and the application is
+    /// terminated after writing it if it contains an error and termination
+    /// on errors is enabled, even if the batch could not be written. Like
+    /// `log`, the write failure policy applies to the records which could
+    /// not be written.
+    ///
+    /// - Arguments:
+    ///   - `records`: The levels and messages of the records to log.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    /// - Panics:
+    ///   - If the batch cannot be written and the write failure policy is
+    ///     `WriteFailurePolicy::Panic`.
+    ///
+    pub fn log_batch(&self, records: &[(LogLevel, &str)]) -> Result<(), std::io::Error> {
+        let separated =
+            self.line_termination == LineTermination::Separated && self.format != LogFormat::Binary;
+        let mut written = Vec::new();
+        let mut console_lines = Vec::new();
+        let mut encoded = Vec::new();
+        let mut data = Vec::new();
+        let mut most_severe: Option<LogLevel> = None;
+
+        for &(level, message) in records {
+            if !self.enabled(level) {
+                continue;
+            }
+
+            if let Some(filter) = &self.filter {
+                if !(filter.0)(level, message) {
+                    continue;
+                }
+            }
+
+            let mut record = self.prepare_record(None, level, message, &[]);
+            self.stamp_record(&mut record);
+
+            let (console_line, record_data) = self.encode_output(&record);
+
+            if separated && !data.is_empty() {
+                data.extend_from_slice(self.line_ending.as_str().as_bytes());
+            }
+
+            data.extend_from_slice(&record_data);
+            encoded.push(record_data);
+            console_lines.push(console_line);
+
+            if most_severe.is_none_or(|severe| level.severity() > severe.severity()) {
+                most_severe = Some(level);
+            }
+
+            written.push(record);
+        }
+
+        let Some(most_severe) = most_severe else {
+            return Ok(());
+        };
+
+        let terminate = most_severe == LogLevel::Error && self.terminate_on_error();
+        let mut persisted = Vec::with_capacity(written.len());
+        let mut failed = Vec::new();
+        let mut failure = None;
+        //
+        // A spool directory receives each record in its own file, so only
+        // the records which could not be written are recovered.
+        //
+        if self.is_spool() {
+            for (record, record_data) in written.iter().zip(&encoded) {
+                match self.persist(record.level, record_data) {
+                    Ok(()) => persisted.push(true),
+                    Err(error) => {
+                        failed.extend_from_slice(record_data);
+                        failure = Some(error);
+                        persisted.push(false);
+                    }
+                }
+            }
+        } else {
+            if let Err(error) = self.persist(most_severe, &data) {
+                failed = data;
+                failure = Some(error);
+            }
+
+            persisted.resize(written.len(), failure.is_none());
+        }
+
+        let result = console_lines
+            .iter()
+            .zip(&persisted)
+            .try_for_each(|(console_line, &persisted)| {
+                if persisted {
+                    self.print(console_line)
+                } else {
+                    self.print(&format!("{} [NOT PERSISTED]", console_line))
+                }
+            })
+            .and_then(|()| match failure {
+                Some(error) => self.recover_write(&failed, error),
+                None => Ok(()),
+            });
+        //
+        // A write failure must not prevent the termination.
+        //
+        if terminate {
+            self.terminate();
+        }
+
+        result.expect(WRITE_ERROR);
+
+        for record in &written {
+            self.record_written(record);
+            self.counters.increment(record.level);
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Log a single record to a specific file instead of the log
+    /// destination, e.g. to set one record of a batch job aside. The file
+    /// is created if needed and appended to; the record is printed on the
+    /// screen as usual. The minimum level and the suspension apply, while
+    /// the filter, the sampling and the collapsing of repeated records do
+    /// not. As with `log`, a record which cannot be written is handled by
+    /// the write failure policy, and the application is terminated after
+    /// an error message if termination on errors is enabled.
+    ///
+    /// - Arguments:
+    ///   - `path`: The path of the file to which the record is written.
+    ///   - `level`: The level of the message.
+    ///   - `message`: The message to log.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    /// - Panics:
+    ///   - If the record cannot be written and the write failure policy is
+    ///     `WriteFailurePolicy::Panic`.
+    ///
+    pub fn log_to(&self, path: &Path, level: LogLevel, message: &str) -> Result<(), LoggerError> {
+        if !self.enabled(level) {
+            return Ok(());
+        }
+
+        let record = self.prepare_record(None, level, message, &[]);
+        let (console_line, data) = self.encode_output(&record);
+        let terminate = level == LogLevel::Error && self.terminate_on_error();
+
+        let result = match OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(&data))
+        {
+            Ok(()) => self.print(&console_line),
+            Err(error) => self
+                .print(&format!("{} [NOT PERSISTED]", console_line))
+                .and_then(|()| self.recover_write_elsewhere(&data, error)),
+        };
+        //
+        // A write failure must not prevent the termination.
+        //
+        if terminate {
+            self.terminate();
+        }
+
+        result.expect(WRITE_ERROR);
+        self.counters.increment(level);
+
+        Ok(())
+    }
+
+    ///
+    /// Install a panic hook which logs the payload and the location of any
+    /// panic at the `Error` level, before the previously installed hook,
+    /// normally the default one, runs. The record bypasses the suspension,
+    /// the minimum level, the filter and the sampling, and never
+    /// terminates the application, so that the panic proceeds as usual.
+    /// If the panic is raised while the log destination or the console is
+    /// locked, e.g. by a panicking writer, the record is written on the
+    /// standard error instead, since waiting for the lock could deadlock.
+    ///
+    /// - Arguments:
+    ///   - `self`: The shared `Logger` to which the panics are logged.
+    ///
+    pub fn install_panic_hook(self: Arc<Self>) {
+        let previous = std::panic::take_hook();
+
+        std::panic::set_hook(Box::new(move |info| {
+            let payload = info
+                .payload()
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| info.payload().downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("Box<dyn Any>");
+            let message = match info.location() {
+                Some(location) => format!("panicked at {}: {}", location, payload),
+                None => format!("panicked: {}", payload),
+            };
+            //
+            // A failure to log must not cause a panic within the panic hook.
+            //
+            if self.output_is_locked() {
+                eprintln!("[{}] {}", LogLevel::Error, message);
+            } else {
+                let record = self.prepare_record(None, LogLevel::Error, &message, &[]);
+
+                if self.write_output(record).is_ok() {
+                    self.counters.increment(LogLevel::Error);
+                }
+            }
+
+            previous(info);
+        }));
+    }
+
+    ///
+    /// Log a message with a timestamp supplied by the caller instead of
+    /// the current time, e.g. when replaying historical events. The time
+    /// is rendered according to the timestamp format; the uptime format
+    /// still reports the time elapsed since the creation of the `Logger`.
+    ///
+    /// - Arguments:
+    ///   - `when`: The time of the record.
+    ///   - `level`: The level of the message.
+    ///   - `message`: The message to log.
+    ///
+    pub fn log_at(
+        &self,
+        when: SystemTime,
+        level: LogLevel,
+        message: &str,
+    ) -> Result<(), LoggerError> {
+        self.log_record(Some(when), None, level, message, &[])
+    }
+
+    ///
+    /// Log a record, applying the suspension, the minimum level, the
+    /// filter and the collapsing of repeated records.
+    ///
+    /// - Arguments:
+    ///   - `time`: The time of the record, or `None` for the current time.
+    ///   - `module`: The module path of the caller, if known.
+    ///   - `level`: The level of the message.
+    ///   - `message`: The message to log.
+    ///   - `fields`: The key-value fields of the record.
+    ///
+    fn log_record(
+        &self,
+        time: Option<SystemTime>,
+        module: Option<&'static str>,
+        level: LogLevel,
+        message: &str,
+        fields: &[(&str, &dyn Display)],
+    ) -> Result<(), LoggerError> {
+        //
+        // Drop the record if logging is suspended or its level is below the
+        // minimum level.
+        //
+        if !self.enabled(level) {
+            return Ok(());
+        }
+        //
+        // Drop the record if it is rejected by the filter.
+        //
+        if let Some(filter) = &self.filter {
+            if !(filter.0)(level, message) {
+                return Ok(());
+            }
+        }
+        //
+        // Drop the record if it is not kept by the sampling of its level.
+        //
+        let rate = self.sample_rates[level.severity() as usize];
+
+        if rate > 1 && self.sample_counters.increment(level) % rate != 0 {
+            return Ok(());
+        }
+        //
+        // Collapse the record if it repeats the previous one, otherwise
+        // report how many times the previous one was repeated.
+        //
+        if let Some(last_record) = &self.last_record {
+            let mut last_record = last_record.lock().unwrap_or_else(PoisonError::into_inner);
+
+            if let Some(last) = last_record.as_mut() {
+                if last.level == level && last.message == message {
+                    last.repeats += 1;
+                    self.counters.increment(level);
+                    return Ok(());
+                }
+            }
+
+            let previous = last_record.replace(LastRecord {
+                level,
+                message: message.to_string(),
+                repeats: 0,
+            });
+            drop(last_record);
+
+            if let Some(previous) = previous {
+                self.write_repeats(&previous).expect(WRITE_ERROR);
+            }
+        }
+        //
+        // Log the message on the screen and the log destination.
+        //
+        let mut record = self.prepare_record(time, level, message, fields);
+
+        if self.include_module_path {
+            record.module = module;
+        }
+
+        let terminate = level == LogLevel::Error && self.terminate_on_error();
+        let result = self.write_output(record);
+        //
+        // If the level is `Error`, then we need to terminate the application.
+        // A write failure must not prevent the termination.
+        //
+        if terminate {
+            self.terminate();
+        }
+
+        result.expect(WRITE_ERROR);
+        self.counters.increment(level);
+
+        Ok(())
+    }
+
+    ///
+    /// Render a record for the screen and encode it for the log
+    /// destination, each in its own format.
+    ///
+    /// - Arguments:
+    ///   - `record`: The record to render.
+    ///
+    /// - Returns:
+    ///   - The line to print on the screen and the bytes to write to the
+    ///     log destination, including the line terminator.
+    ///
+    fn encode_output(&self, record: &Record) -> (String, Vec<u8>) {
+        let level = record.level;
+        let console_line =
+            self.decorate_console_line(level, &self.render_record(self.console_format(), record));
+        //
+        // The escape sequences of a message formatted for a terminal never
+        // reach the log destination, in any format.
+        //
+        let stripped;
+        let record = if record.message.contains('\x1b')
+            || record
+                .fields
+                .iter()
+                .any(|(_, value)| value.contains('\x1b'))
+        {
+            stripped = Record {
+                message: strip_ansi(&record.message).into_owned(),
+                fields: record
+                    .fields
+                    .iter()
+                    .map(|&(key, ref value)| (key, strip_ansi(value).into_owned()))
+                    .collect(),
+                ..*record
+            };
+            &stripped
+        } else {
+            record
+        };
+        let data = match self.format {
+            LogFormat::Binary => {
+                let message = format!("{}{}", record.message, self.render_fields(record));
+                encode_record(level, record.time, &message)
+            }
+            format => self.terminate_line(&strip_ansi(&self.render_record(format, record))),
+        };
+
+        (console_line, data)
+    }
+
+    ///
+    /// Write a record on the screen and the log destination, each in its
+    /// own format, assigning its sequence number if enabled.
+    ///
+    /// - Arguments:
+    ///   - `record`: The record to write.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    fn write_output(&self, mut record: Record) -> Result<(), std::io::Error> {
+        let level = record.level;
+        self.stamp_record(&mut record);
+
+        let (console_line, data) = self.encode_output(&record);
+
+        if let Err(error) = self.write_lines(level, &console_line, &data) {
+            self.recover_write(&data, error)?;
+        }
+
+        self.record_written(&record);
+
+        Ok(())
+    }
+
+    ///
+    /// Update the statistics after a record was written.
+    ///
+    /// - Arguments:
+    ///   - `record`: The written record.
+    ///
+    fn record_written(&self, record: &Record) {
+        self.output_counters.set_last_record_time(record.time);
+
+        #[cfg(any(test, feature = "test-util"))]
+        if record.level.severity() >= LogLevel::Warning.severity() {
+            self.issues
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(self.render_record(LogFormat::Plain, record));
+        }
+    }
+
+    ///
+    /// Apply the write failure policy to a record which could not be
+    /// written to the log destination.
+    ///
+    /// - Arguments:
+    ///   - `data`: The encoded record which could not be written.
+    ///   - `error`: The error which occurred.
+    ///
+    /// - Returns:
+    ///   - Success if the record was discarded or written to the fallback
+    ///     file, otherwise the error to report.
+    ///
+    fn recover_write(&self, data: &[u8], error: std::io::Error) -> Result<(), std::io::Error> {
+        match &self.write_failure_policy {
+            WriteFailurePolicy::Panic => Err(error),
+            WriteFailurePolicy::Drop => {
+                self.output_counters.add_dropped();
+                Ok(())
+            }
+            WriteFailurePolicy::Fallback(path) => {
+                let file = OpenOptions::new().create(true).append(true).open(path)?;
+                let mut destination = self.lock_destination();
+
+                destination.target = Target::File(file);
+                destination.write_line(data)?;
+                destination.flush()
+            }
+        }
+    }
+
+    ///
+    /// Apply the write failure policy to a record which could not be
+    /// written to a file other than the log destination. Unlike
+    /// `recover_write`, the fallback file receives only this record and
+    /// does not replace the log destination.
+    ///
+    /// - Arguments:
+    ///   - `data`: The encoded record which could not be written.
+    ///   - `error`: The error which occurred.
+    ///
+    /// - Returns:
+    ///   - Success if the record was discarded or written to the fallback
+    ///     file, otherwise the error to report.
+    ///
+    fn recover_write_elsewhere(
+        &self,
+        data: &[u8],
+        error: std::io::Error,
+    ) -> Result<(), std::io::Error> {
+        match &self.write_failure_policy {
+            WriteFailurePolicy::Fallback(path) => OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?
+                .write_all(data),
+            _ => self.recover_write(data, error),
+        }
+    }
+
+    ///
+    /// Append the line ending to a line, unless the line endings separate
+    /// the records instead of terminating them.
+    ///
+    /// - Arguments:
+    ///   - `line`: The line to terminate.
+    ///
+    /// - Returns:
+    ///   - The bytes of the line to write.
+    ///
+    fn terminate_line(&self, line: &str) -> Vec<u8> {
+        match self.line_termination {
+            LineTermination::Terminated => format!("{}{}", line, self.line_ending.as_str()),
+            LineTermination::Separated => line.to_string(),
+        }
+        .into_bytes()
+    }
+
+    ///
+    /// Check whether a log file ends with a record which is not followed
+    /// by a line ending, when the line endings separate the records.
+    ///
+    /// - Arguments:
+    ///   - `path`: The path of the log file.
+    ///
+    /// - Returns:
+    ///   - True if the next record must be preceded by a line ending.
+    ///
+    fn ends_with_partial_line(&self, path: &Path) -> Result<bool, std::io::Error> {
+        if self.line_termination == LineTermination::Terminated {
+            return Ok(false);
+        }
+
+        let mut file = File::open(path)?;
+
+        if file.metadata()?.len() == 0 {
+            return Ok(false);
+        }
+
+        let mut last = [0_u8; 1];
+        file.seek(SeekFrom::End(-1))?;
+        file.read_exact(&mut last)?;
+
+        Ok(last[0] != b'\n')
+    }
+
+    ///
+    /// Write an encoded record on the log destination and then a line on
+    /// the screen. The record is written first, so that a line which could
+    /// not be written is marked as `[NOT PERSISTED]` on the screen.
+    ///
+    /// - Arguments:
+    ///   - `level`: The level of the record, which determines when the
+    ///     buffered records are flushed.
+    ///   - `console_line`: The line to print on the screen.
+    ///   - `data`: The encoded record to write to the log destination,
+    ///     including its line terminator.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    fn write_lines(
+        &self,
+        level: LogLevel,
+        console_line: &str,
+        data: &[u8],
+    ) -> Result<(), std::io::Error> {
+        let result = self.persist(level, data);
+
+        match result {
+            Ok(()) => self.print(console_line)?,
+            Err(_) => self.print(&format!("{} [NOT PERSISTED]", console_line))?,
+        }
+
+        result
+    }
+
+    ///
+    /// Print a line on the screen.
+    ///
+    /// - Arguments:
+    ///   - `console_line`: The line to print, excluding the line terminator.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    fn print(&self, console_line: &str) -> Result<(), std::io::Error> {
+        match &self.console {
+            Some(console) => {
+                let mut console = console.0.lock().unwrap_or_else(PoisonError::into_inner);
+                writeln!(console, "{}", console_line)?;
+
+                if self.flush_console {
+                    console.flush()?;
+                }
+            }
+            None => {
+                println!("{}", console_line);
+
+                if self.flush_console {
+                    std::io::stdout().flush()?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Write an encoded record on the log destination.
+    ///
+    /// - Arguments:
+    ///   - `level`: The level of the record, which determines when the
+    ///     buffered records are flushed.
+    ///   - `data`: The encoded record, including its line terminator.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    fn persist(&self, level: LogLevel, data: &[u8]) -> Result<(), std::io::Error> {
+        let mut destination = self.lock_destination();
+        self.switch_period(&mut destination)?;
+        self.open_lazily(&mut destination)?;
+
+        let spool = matches!(destination.target, Target::Spool(_));
+        let separated = self.line_termination == LineTermination::Separated
+            && self.format != LogFormat::Binary
+            && !spool;
+
+        let data = if separated && destination.needs_separator {
+            Cow::Owned([self.line_ending.as_str().as_bytes(), data].concat())
+        } else {
+            Cow::Borrowed(data)
+        };
+
+        destination.buffer_line(&data);
+        destination.needs_separator = separated;
+        self.output_counters.add_bytes(data.len());
+        //
+        // A spool directory receives each record in its own file, so it is
+        // never buffered.
+        //
+        let flush = spool
+            || self.flush_policy.is_due(
+                level,
+                destination.pending_records(),
+                destination.pending_age(),
+            );
+
+        if flush {
+            destination.flush()?;
+
+            if let (Some(max_file_size), Some(size)) =
+                (self.max_file_size, file_size(&destination)?)
+            {
+                if size >= max_file_size {
+                    self.rotate_destination(&mut destination)?;
+                }
+            }
+        }
+
+        if self.flush_policy.syncs(level) {
+            destination.sync()?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Write the `last message repeated N times` line of a collapsed
+    /// record, if it was repeated.
+    ///
+    /// - Arguments:
+    ///   - `record`: The collapsed record.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    fn write_repeats(&self, record: &LastRecord) -> Result<(), std::io::Error> {
+        if record.repeats == 0 {
+            return Ok(());
+        }
+
+        let message = format!("last message repeated {} times", record.repeats);
+        self.write_output(self.prepare_record(None, record.level, &message, &[]))
+    }
+
+    ///
+    /// Set a hook which is invoked right before the application is
+    /// terminated because of a logged error, e.g. to run cleanup code or
+    /// send an alert. The hook may exit the process itself; if it returns,
+    /// the default termination follows.
+    ///
+    /// - Arguments:
+    ///   - `on_terminate`: The hook to invoke.
+    ///
+    pub fn set_on_terminate(&mut self, on_terminate: Box<LogTerminateHook>) {
+        self.on_terminate = Some(Shared(Arc::from(on_terminate)));
+    }
+
+    ///
+    /// Set the exit code of the process when it is terminated because of a
+    /// logged error, so that supervisors and scripts can tell a shutdown
+    /// initiated by the logger from other failures. The default is 1.
+    ///
+    /// - Arguments:
+    ///   - `error_exit_code`: The exit code to use.
+    ///
+    pub fn set_error_exit_code(&mut self, error_exit_code: i32) {
+        self.error_exit_code = error_exit_code;
+    }
+
+    ///
+    /// Set the behaviour when a record cannot be written to the log
+    /// destination, e.g. because the disk is full. By default the
+    /// application panics; the record may instead be discarded and counted
+    /// in `LoggerStats::dropped_records`, or the logging may switch to an
+    /// alternate file.
+    ///
+    /// - Arguments:
+    ///   - `write_failure_policy`: The policy to apply.
+    ///
+    pub fn set_write_failure_policy(&mut self, write_failure_policy: WriteFailurePolicy) {
+        self.write_failure_policy = write_failure_policy;
+    }
+
+    ///
+    /// Enable or disable buffered logging, i.e. the `FlushPolicy::EveryN`
+    /// policy. When enabled, the records are held in memory and written to
+    /// the log destination in a single batch once the specified number of
+    /// records has accumulated.
+    ///
+    /// - Arguments:
+    ///   - `flush_every`: The number of records to buffer, or `None` to
+    ///     write every record immediately.
+    ///
+    pub fn set_flush_every(&mut self, flush_every: Option<usize>) {
+        self.flush_policy = match flush_every {
+            Some(records) => FlushPolicy::EveryN(records),
+            None => FlushPolicy::EveryLine,
+        };
+    }
+
+    ///
+    /// Enable or disable the collapsing of consecutive identical records.
+    /// When enabled, a record with the same level and message as the
+    /// previous one is not written; instead, a `last message repeated N
+    /// times` line is written when a different record arrives or when the
+    /// logger is synchronised.
+    ///
+    /// - Arguments:
+    ///   - `deduplicate`: If true then collapse consecutive identical records.
+    ///
+    pub fn set_deduplicate(&mut self, deduplicate: bool) {
+        self.last_record = deduplicate.then(|| Arc::new(Mutex::new(None)));
+    }
+
+    ///
+    /// Log an error at the `Error` level together with its complete chain
+    /// of sources, e.g. `top error: caused by: root error`.
+    ///
+    /// - Arguments:
+    ///   - `error`: The error to log.
+    ///
+    pub fn log_error_chain(&self, error: &dyn std::error::Error) -> Result<(), LoggerError> {
+        let mut message = error.to_string();
+        let mut source = error.source();
+
+        while let Some(cause) = source {
+            message.push_str(&format!(": caused by: {}", cause));
+            source = cause.source();
+        }
+
+        self.log(LogLevel::Error, &message)
+    }
+
+    ///
+    /// Log a message followed by the backtrace of the current thread as
+    /// indented continuation lines. The backtrace is captured only if
+    /// enabled through the `RUST_LIB_BACKTRACE` or `RUST_BACKTRACE`
+    /// environment variables; otherwise only the message is logged.
+    ///
+    /// - Arguments:
+    ///   - `level`: The level of the message.
+    ///   - `message`: The message to log.
+    ///
+    pub fn log_with_backtrace(&self, level: LogLevel, message: &str) -> Result<(), LoggerError> {
+        if !self.enabled(level) {
+            return Ok(());
+        }
+
+        let backtrace = std::backtrace::Backtrace::capture();
+
+        if backtrace.status() != std::backtrace::BacktraceStatus::Captured {
+            return self.log(level, message);
+        }
+
+        let mut message = message.to_string();
+
+        for line in backtrace.to_string().lines() {
+            message.push_str(&format!("\n    {}", line));
+        }
+
+        self.log(level, &message)
+    }
+
+    ///
+    /// Log the error of a `Result`, if any, and pass the `Result` through
+    /// unchanged, e.g. `logger.log_err(file.read(&mut buf), LogLevel::Error)?`.
+    /// A failure to log the error does not replace it, since the caller is
+    /// interested in the original error.
+    ///
+    /// - Arguments:
+    ///   - `result`: The result to inspect.
+    ///   - `level`: The level of the logged error.
+    ///
+    /// - Returns:
+    ///   - The same `result`.
+    ///
+    pub fn log_err<T, E: Display>(&self, result: Result<T, E>, level: LogLevel) -> Result<T, E> {
+        if let Err(error) = &result {
+            let _ = self.log(level, &error.to_string());
+        }
+
+        result
+    }
+
+    ///
+    /// Terminate the application after an error message has been logged.
+    /// The log destination is flushed on a best-effort basis, ignoring any
+    /// further I/O errors, and the process exits with the configured exit
+    /// code rather than panicking, so an intentional shutdown produces no
+    /// backtrace.
+    ///
+    fn terminate(&self) -> ! {
+        if let Ok(mut destination) = self.destination.0.try_lock() {
+            let _ = destination.flush();
+        }
+
+        terminate_process(
+            self.on_terminate.as_ref().map(|hook| &*hook.0),
+            self.error_exit_code,
+        )
+    }
+
+    ///
+    /// Replace the whole contents of the log file with a snapshot, such as
+    /// the current status of the application. The snapshot is written to a
+    /// temporary file in the same directory, which is then atomically
+    /// renamed over the log file, so readers never observe a partial file.
+    /// Subsequent calls to `log` append to the new file.
+    ///
+    /// - Arguments:
+    ///   - `content`: The complete contents of the snapshot.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    pub fn write_snapshot(&self, content: &str) -> Result<(), std::io::Error> {
+        let mut destination = self.lock_destination();
+
+        if let Target::Writer(_) = destination.target {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "Logger: a writer destination cannot hold a snapshot.",
+            ));
+        }
+
+        let path = self.active_path(&destination);
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let temp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+
+        destination.flush()?;
+
+        let mut temp_file = File::create(&temp_path)?;
+        temp_file.write_all(content.as_bytes())?;
+        temp_file.sync_all()?;
+        drop(temp_file);
+
+        if let Err(error) = std::fs::rename(&temp_path, &path) {
+            let _ = std::fs::remove_file(&temp_path);
+            return Err(error);
+        }
+
+        destination.target = Target::File(OpenOptions::new().append(true).open(&path)?);
+        destination.needs_separator = self.ends_with_partial_line(&path)?;
+
+        Ok(())
+    }
+
+    ///
+    /// Suspend logging. Until `resume` is called, all the records are
+    /// dropped and `log` returns `Ok(())` without any output.
+    ///
+    pub fn suspend(&self) {
+        self.suspended.store(true, Ordering::Relaxed);
+    }
+
+    ///
+    /// Resume logging after a call to `suspend`.
+    ///
+    pub fn resume(&self) {
+        self.suspended.store(false, Ordering::Relaxed);
+    }
+
+    ///
+    /// Write all the buffered records, including the pending count of a
+    /// collapsed record, and flush the log destination. It is always safe
+    /// to call, and cheap when nothing is buffered.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    pub fn flush(&self) -> Result<(), std::io::Error> {
+        self.flush_repeats()?;
+        self.lock_destination().flush()
+    }
+
+    ///
+    /// Synchronise the log file to the disk, guaranteeing that all the
+    /// logged messages survive a power loss. Unlike the flush performed by
+    /// `log`, this waits for the storage device and is therefore expensive;
+    /// call it at checkpoints rather than after every message. For a
+    /// writer destination this only flushes the writer.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    pub fn sync(&self) -> Result<(), std::io::Error> {
+        self.flush_repeats()?;
+        self.lock_destination().sync()
+    }
+
+    ///
+    /// Shut the logger down when the application exits: logging is
+    /// suspended for all the clones of this `Logger`, and every pending
+    /// record, whether buffered, collapsed or queued by a writer such as
+    /// `TcpSink`, is written and synchronised to the disk. The draining
+    /// runs on a separate thread, so that a destination which hangs, e.g.
+    /// a stalled network file system, cannot block the exit beyond the
+    /// timeout.
+    ///
+    /// - Arguments:
+    ///   - `timeout`: The maximum time to wait for the draining.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure. If the draining does not
+    ///     complete in time, an I/O error of kind `TimedOut` is returned
+    ///     and the draining continues in the background.
+    ///
+    pub fn shutdown(&self, timeout: std::time::Duration) -> Result<(), LoggerError> {
+        self.suspend();
+
+        let logger = self.clone();
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        std::thread::spawn(move || {
+            let _ = sender.send(logger.sync());
+        });
+
+        match receiver.recv_timeout(timeout) {
+            Ok(result) => Ok(result?),
+            Err(_) => Err(LoggerError::Io(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "Logger: the pending records were not drained in time.",
+            ))),
+        }
+    }
+
+    ///
+    /// Write the pending `last message repeated N times` line of the
+    /// collapsed record, if any.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    fn flush_repeats(&self) -> Result<(), std::io::Error> {
+        if let Some(last_record) = &self.last_record {
+            let mut last_record = last_record.lock().unwrap_or_else(PoisonError::into_inner);
+
+            if let Some(last) = last_record.as_mut() {
+                self.write_repeats(last)?;
+                last.repeats = 0;
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Read the last lines of the log file. The file is read backwards in
+    /// chunks, so only its tail is loaded in memory. The continuation lines
+    /// of multiline messages are returned as separate lines.
+    ///
+    /// - Arguments:
+    ///   - `n`: The maximum number of lines to return.
+    ///
+    /// - Returns:
+    ///   - The last `n` lines of the log file, or fewer if the file is
+    ///     shorter, in the order they were written.
+    ///
+    pub fn tail(&self, n: usize) -> Result<Vec<String>, std::io::Error> {
+        const CHUNK_SIZE: u64 = 4096;
+
+        let path = {
+            let destination = self.lock_destination();
+
+            if let Target::Writer(_) = destination.target {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::Unsupported,
+                    "Logger: a writer destination cannot be read back.",
+                ));
+            }
+
+            self.active_path(&destination)
+        };
+
+        if n == 0 {
+            return Ok(Vec::new());
+        }
+        //
+        // Read chunks from the end of the file until it contains more line
+        // terminators than the requested lines, which guarantees that the
+        // last `n` lines are complete.
+        //
+        let mut file = File::open(&path)?;
+        let mut position = file.metadata()?.len();
+        let mut buffer: Vec<u8> = Vec::new();
+
+        while position > 0 && buffer.iter().filter(|&&byte| byte == b'\n').count() <= n {
+            let size = CHUNK_SIZE.min(position);
+            position -= size;
+
+            let mut chunk = vec![0; size as usize];
+            file.seek(SeekFrom::Start(position))?;
+            file.read_exact(&mut chunk)?;
+            chunk.extend_from_slice(&buffer);
+            buffer = chunk;
+        }
+
+        let text = String::from_utf8_lossy(&buffer);
+        let lines: Vec<&str> = text.lines().collect();
+
+        Ok(lines[lines.len().saturating_sub(n)..]
+            .iter()
+            .map(|line| line.to_string())
+            .collect())
+    }
+
+    ///
+    /// Write the byte order mark, if enabled, and the header line, if any,
+    /// to a newly created log file.
+    ///
+    /// - Arguments:
+    ///   - `file`: The newly created log file.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    fn write_preamble(&self, file: &mut File) -> Result<(), std::io::Error> {
+        self.write_bom(file)?;
+        self.write_header(file)
+    }
+
+    ///
+    /// Create the log file of a lazy destination, writing its preamble.
+    ///
+    /// - Arguments:
+    ///   - `destination`: The locked destination.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    fn open_lazily(&self, destination: &mut Destination) -> Result<(), std::io::Error> {
+        if let Target::Lazy(path) = &destination.target {
+            let mut file = create_log_file(path)?;
+            self.write_preamble(&mut file)?;
+            destination.target = Target::File(file);
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Write the byte order mark, if enabled, to a newly created log file.
+    ///
+    /// - Arguments:
+    ///   - `file`: The newly created log file.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    fn write_bom(&self, file: &mut File) -> Result<(), std::io::Error> {
+        if self.write_bom && self.format != LogFormat::Binary {
+            file.write_all(UTF8_BOM)?;
+            file.flush()?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Write the header line, if any, to a newly created log file.
+    ///
+    /// - Arguments:
+    ///   - `file`: The newly created log file.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    fn write_header(&self, file: &mut File) -> Result<(), std::io::Error> {
+        if self.format == LogFormat::Binary {
+            return Ok(());
+        }
+
+        if let Some(header) = &self.header {
+            let line = format!("{}{}", (header.0)(), self.line_ending.as_str());
+            file.write_all(line.as_bytes())?;
+            file.flush()?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Check whether the log destination is a spool directory, which
+    /// receives each record in its own file.
+    ///
+    /// - Returns:
+    ///   - True if the log destination is a spool directory.
+    ///
+    fn is_spool(&self) -> bool {
+        matches!(self.lock_destination().target, Target::Spool(_))
+    }
+
+    ///
+    /// Check whether the log destination or the console is locked, by this
+    /// or another thread, without waiting for it.
+    ///
+    /// - Returns:
+    ///   - True if writing a record would wait for a lock.
+    ///
+    fn output_is_locked(&self) -> bool {
+        let destination = matches!(self.destination.0.try_lock(), Err(TryLockError::WouldBlock));
+        let console = self
+            .console
+            .as_ref()
+            .is_some_and(|console| matches!(console.0.try_lock(), Err(TryLockError::WouldBlock)));
+
+        destination || console
+    }
+
+    ///
+    /// Lock the destination of the logged lines. A destination poisoned by
+    /// a panic in another thread is still usable, since every line is
+    /// written with a single call.
+    ///
+    /// - Returns:
+    ///   - The guard of the locked destination.
+    ///
+    fn lock_destination(&self) -> MutexGuard<'_, Destination> {
+        self.destination
+            .0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+///
+/// The indentation of the messages per enclosing scope.
+///
+const SCOPE_INDENT: &str = "  ";
+
+thread_local! {
+    ///
+    /// The number of scopes entered by the current thread.
+    ///
+    static SCOPE_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+///
+/// A guard which leaves a nested scope when it is dropped, created by
+/// `Logger::enter_scope`. It must be dropped by the thread which entered
+/// the scope, so it is neither `Send` nor `Sync`.
+///
+#[derive(Debug)]
+#[must_use = "the scope is left as soon as the guard is dropped"]
+pub struct ScopeGuard {
+    ///
+    /// A marker binding the guard to the current thread.
+    ///
+    _thread: std::marker::PhantomData<*const ()>,
+}
+
+//
+// Implementation of the `Drop` trait for `ScopeGuard`.
+//
+impl Drop for ScopeGuard {
+    ///
+    /// Leave the scope.
+    ///
+    fn drop(&mut self) {
+        SCOPE_DEPTH.with(|depth| depth.set(depth.get().saturating_sub(1)));
+    }
+}
+
+///
+/// A guard which prevents error messages from terminating the application
+/// while it is alive, created by `Logger::suppress_terminate`.
+///
+#[derive(Debug)]
+#[must_use = "the termination is only suppressed while the guard is alive"]
+pub struct TerminateGuard<'a> {
+    ///
+    /// The logger whose termination is suppressed.
+    ///
+    logger: &'a Logger,
+    ///
+    /// The value of the flag before the suppression.
+    ///
+    previous: bool,
+}
+
+//
+// Implementation of the `Drop` trait for `TerminateGuard`.
+//
+impl Drop for TerminateGuard<'_> {
+    ///
+    /// Restore the termination behavior which preceded the suppression.
+    ///
+    fn drop(&mut self) {
+        self.logger
+            .terminate_on_error
+            .store(self.previous, Ordering::Relaxed);
+    }
+}
+
+///
+/// The value of `Logger::verbose_severity` when the minimum level is not
+/// overridden.
+///
+const NO_OVERRIDE: u8 = u8::MAX;
+
+///
+/// A guard which restores the minimum level overridden by
+/// `Logger::with_verbose` when it is dropped, including on a panic.
+///
+struct VerboseGuard<'a> {
+    ///
+    /// The logger whose minimum level is overridden.
+    ///
+    logger: &'a Logger,
+    ///
+    /// The override which preceded this one, if any.
+    ///
+    previous: u8,
+}
+
+//
+// Implementation of the `Drop` trait for `VerboseGuard`.
+//
+impl Drop for VerboseGuard<'_> {
+    ///
+    /// Restore the override which preceded this one.
+    ///
+    fn drop(&mut self) {
+        self.logger
+            .verbose_severity
+            .store(self.previous, Ordering::Relaxed);
+    }
+}
+
+///
+/// Resolve the path of a log file, using `default.log` for an empty path.
+///
+/// - Arguments:
+///   - `log_file_path`: The path given by the caller.
+///
+/// - Returns:
+///   - The path of the log file, or `LoggerError::PathIsDirectory` if it
+///     is an existing directory.
+///
+pub(crate) fn resolve_log_path(log_file_path: &str) -> Result<String, LoggerError> {
+    let log_file = if log_file_path.is_empty() {
+        "default.log".to_string()
+    } else {
+        log_file_path.to_string()
+    };
+
+    if Path::new(&log_file).is_dir() {
+        return Err(LoggerError::PathIsDirectory(log_file));
+    }
+
+    Ok(log_file)
+}
+
+///
+/// Terminate the application after an error message has been logged,
+/// invoking the termination hook, if any, and exiting with the exit code.
+///
+/// - Arguments:
+///   - `on_terminate`: The hook to invoke before exiting.
+///   - `error_exit_code`: The exit code of the process.
+///
+pub(crate) fn terminate_process(
+    on_terminate: Option<&LogTerminateHook>,
+    error_exit_code: i32,
+) -> ! {
+    if let Some(on_terminate) = on_terminate {
+        on_terminate();
+    }
+
+    eprintln!("Logger: Application terminated abnormally.");
+    std::process::exit(error_exit_code);
+}
+
+///
+/// Create the missing parent directories of a log file.
+///
+/// - Arguments:
+///   - `log_file_path`: The path of the log file.
+///
+/// - Returns:
+///   - A result indicating success or failure.
+///
+fn create_parent_dirs(log_file_path: &str) -> Result<(), std::io::Error> {
+    match Path::new(log_file_path).parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => std::fs::create_dir_all(parent),
+        _ => Ok(()),
+    }
+}
+
+///
+/// Get the size of the log file of a locked destination.
+///
+/// - Arguments:
+///   - `destination`: The locked destination.
+///
+/// - Returns:
+///   - The size of the log file in bytes, or `None` for a writer.
+///
+fn file_size(destination: &Destination) -> Result<Option<u64>, std::io::Error> {
+    match &destination.target {
+        Target::File(file) => Ok(Some(file.metadata()?.len())),
+        Target::Writer(_) | Target::Spool(_) => Ok(None),
+        Target::Lazy(_) => Ok(Some(0)),
+    }
+}
+
+///
+/// Get the hostname of the machine. It is read from the kernel on Linux
+/// and from the environment elsewhere.
+///
+/// - Returns:
+///   - The hostname, or `localhost` if it cannot be determined.
+///
+fn hostname() -> String {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .ok()
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "localhost".to_string())
+}
+
+//
+// Implementation of the `Default` trait for `Logger`.
+//
+impl Default for Logger {
+    ///
+    /// Create a default `Logger` which logs to `default.log`. It does not
+    /// terminate the application when an error message is logged, since a
+    /// routine error report should never abort the process unexpectedly;
+    /// use `Logger::new("default.log", true)` to opt into termination.
+    ///
+    /// - Returns:
+    ///   - The default `Logger` object.
+    ///
+    fn default() -> Self {
+        Logger::new("default.log", false)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//
+// Unit Tests.
+//
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary::{LogRecord, read_binary_log};
+    use crate::clock::FixedClock;
+    use crate::format::TRUNCATION_MARKER;
+    use crate::rotation::backup_path;
+    use rand::prelude::*;
+    use std::fs;
+    use std::path::Path;
+    use std::time::{Duration, SystemTime};
+
+    //
+    // An in-memory writer whose contents remain accessible to the test.
+    //
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    //
+    // A writer which always fails, like a file on a full disk.
+    //
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::StorageFull.into())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    //
+    // Helper function to generate unique log filenames for tests
+    //
+    fn get_unique_log_filename() -> String {
+        let mut rng = rand::rng();
+        format!("test_log_{}.log", rng.random::<u64>())
+    }
+
+    #[test]
+    fn test_logger_new_creates_file() {
+        let log_file = get_unique_log_filename();
+        let _logger = Logger::new(&log_file, true);
+        assert!(Path::new(&log_file).exists());
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Logger: I cannot create the log file")]
+    fn test_logger_new_invalid_path() {
+        let invalid_path = "/nonexistent/directory/test.log";
+        let _logger = Logger::new(invalid_path, true);
+    }
+
+    #[test]
+    fn test_logger_try_new_invalid_path() {
+        let invalid_path = "/nonexistent/directory/test.log";
+        let result = Logger::try_new(invalid_path, true);
+        assert!(matches!(result, Err(LoggerError::Io(_))));
+    }
+
+    #[test]
+    fn test_logger_log_info_message() {
+        let log_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, true);
+
+        logger.log(LogLevel::Info, "Test info message").unwrap();
+
+        let mut file = File::open(&log_file).unwrap();
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).unwrap();
+
+        assert!(contents.contains("[INFO] Test info message"));
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_log_warning_message() {
+        let log_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, true);
+
+        logger
+            .log(LogLevel::Warning, "Test warning message")
+            .unwrap();
+
+        let mut file = File::open(&log_file).unwrap();
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).unwrap();
+
+        assert!(contents.contains("[WARNING] Test warning message"));
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_log_debug_message() {
+        let log_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, true);
+
+        logger.log(LogLevel::Debug, "Test debug message").unwrap();
+
+        let mut file = File::open(&log_file).unwrap();
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).unwrap();
+
+        assert!(contents.contains("[DEBUG] Test debug message"));
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_log_error_message() {
+        let log_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, false);
+
+        logger.log(LogLevel::Error, "Test error message").unwrap();
+
+        let mut file = File::open(&log_file).unwrap();
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).unwrap();
+
+        assert!(contents.contains("[ERROR] Test error message"));
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_multiple_messages() {
+        let log_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, true);
+
+        logger.log(LogLevel::Info, "First message").unwrap();
+        logger.log(LogLevel::Warning, "Second message").unwrap();
+        logger.log(LogLevel::Debug, "Third message").unwrap();
+
+        let mut file = File::open(&log_file).unwrap();
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).unwrap();
+
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(contents.contains("[INFO] First message"));
+        assert!(contents.contains("[WARNING] Second message"));
+        assert!(contents.contains("[DEBUG] Third message"));
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_multiline_message() {
+        let log_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, true);
+
+        logger.log(LogLevel::Info, "Line 1\nLine 2").unwrap();
+
+        let mut file = File::open(&log_file).unwrap();
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).unwrap();
+
+        assert!(contents.contains("[INFO] Line 1\nLine 2"));
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_special_characters() {
+        let log_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, true);
+
+        logger
+            .log(LogLevel::Info, "Special chars: !@#$%^&*()")
+            .unwrap();
+
+        let mut file = File::open(&log_file).unwrap();
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).unwrap();
+
+        assert!(contents.contains("[INFO] Special chars: !@#$%^&*()"));
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_clone() {
+        let log_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, true);
+        let logger_clone = logger.clone();
+
+        logger.log(LogLevel::Info, "Original logger").unwrap();
+        logger_clone
+            .log(LogLevel::Warning, "Cloned logger")
+            .unwrap();
+
+        let mut file = File::open(&log_file).unwrap();
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).unwrap();
+
+        assert!(contents.contains("[INFO] Original logger"));
+        assert!(contents.contains("[WARNING] Cloned logger"));
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_debug_format() {
+        let log_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, true);
+
+        let debug_output = format!("{:?}", logger);
+        assert!(debug_output.contains(&log_file));
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_format_line() {
+        let log_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, true);
+
+        assert_eq!(
+            logger.format_line(LogLevel::Info, "Formatted message"),
+            "[INFO] Formatted message"
+        );
+        assert_eq!(
+            logger.format_line(LogLevel::Error, "Line 1\nLine 2"),
+            "[ERROR] Line 1\nLine 2"
+        );
+        //
+        // Formatting must not write anything to the log file.
+        //
+        assert!(fs::read_to_string(&log_file).unwrap().is_empty());
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_with_context() {
+        let log_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, true);
+        let context_logger = logger.with_context(&[("request_id", "abc"), ("user", "42")]);
+
+        context_logger.log(LogLevel::Info, "Handled").unwrap();
+        logger.log(LogLevel::Info, "Parent").unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+
+        assert_eq!(lines[0], "[INFO] Handled request_id=abc user=42");
+        assert_eq!(lines[1], "[INFO] Parent");
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_counts() {
+        let log_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, false);
+
+        logger.log(LogLevel::Info, "Info 1").unwrap();
+        logger.log(LogLevel::Info, "Info 2").unwrap();
+        logger.log(LogLevel::Debug, "Debug").unwrap();
+        logger.log(LogLevel::Warning, "Warning 1").unwrap();
+        logger.log(LogLevel::Warning, "Warning 2").unwrap();
+        logger.log(LogLevel::Warning, "Warning 3").unwrap();
+        logger.log(LogLevel::Error, "Error").unwrap();
+
+        let counts = logger.counts();
+        assert_eq!(counts.info, 2);
+        assert_eq!(counts.debug, 1);
+        assert_eq!(counts.warning, 3);
+        assert_eq!(counts.error, 1);
+        assert_eq!(counts.total(), 7);
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_fixed_clock_timestamp() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, true);
+        logger.set_timestamp_format(TimestampFormat::Rfc3339);
+        logger.set_clock(FixedClock::new(
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1_735_825_501),
+        ));
+
+        logger.log(LogLevel::Info, "Timestamped message").unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        assert_eq!(
+            contents,
+            "2025-01-02T13:45:01Z [INFO] Timestamped message\n"
+        );
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_filter() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, true);
+        logger.set_filter(Box::new(|_, message| !message.contains("password")));
+
+        logger.log(LogLevel::Info, "User logged in").unwrap();
+        logger.log(LogLevel::Info, "password=hunter2").unwrap();
+        logger.log(LogLevel::Warning, "Wrong password").unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        assert_eq!(contents, "[INFO] User logged in\n");
+        assert!(!contents.contains("password"));
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_redactor() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, true);
+        logger.set_redactor(|message| {
+            message
+                .chars()
+                .map(|c| if c.is_ascii_digit() { '*' } else { c })
+                .collect()
+        });
+
+        logger
+            .log(LogLevel::Info, "Card 4111-1111 charged")
+            .unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        assert_eq!(contents, "[INFO] Card ****-**** charged\n");
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_sync() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, false);
+        logger.set_durable(true);
+
+        logger.log(LogLevel::Info, "Audit record").unwrap();
+        logger.log(LogLevel::Error, "Durable error").unwrap();
+        assert!(logger.sync().is_ok());
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        assert_eq!(contents, "[INFO] Audit record\n[ERROR] Durable error\n");
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_from_writer() {
+        let buffer = SharedBuffer::default();
+        let logger = Logger::from_writer(buffer.clone(), true);
+
+        logger.log(LogLevel::Info, "In memory").unwrap();
+        logger.log(LogLevel::Warning, "Still in memory").unwrap();
+        assert!(logger.sync().is_ok());
+
+        assert_eq!(
+            buffer.contents(),
+            "[INFO] In memory\n[WARNING] Still in memory\n"
+        );
+    }
+
+    #[test]
+    fn test_logger_crlf_line_ending() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, true);
+        logger.set_line_ending(LineEnding::CrLf);
+
+        logger.log(LogLevel::Info, "Windows line").unwrap();
+
+        let contents = fs::read(&log_file).unwrap();
+        assert_eq!(contents, b"[INFO] Windows line\r\n");
+        assert!(contents.ends_with(b"\r\n"));
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_escape_control_chars() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, true);
+        logger.set_control_chars(ControlChars::Escape);
+
+        logger
+            .log(LogLevel::Info, "\x1b[31mRed\x1b[0m\nNext line")
+            .unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        assert_eq!(contents, "[INFO] \\x1b[31mRed\\x1b[0m\nNext line\n");
+        assert!(!contents.contains('\x1b'));
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_uptime_timestamp() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, true);
+        logger.set_timestamp_format(TimestampFormat::UptimeSeconds);
+
+        logger.log(LogLevel::Info, "First").unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        logger.log(LogLevel::Info, "Second").unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        let uptimes: Vec<f64> = contents
+            .lines()
+            .map(|line| {
+                let end = line.find("s] ").unwrap();
+                line[2..end].parse().unwrap()
+            })
+            .collect();
+
+        assert!(contents.lines().all(|line| line.starts_with("[+")));
+        assert!(uptimes[1] > uptimes[0]);
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_tail() {
+        let log_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, true);
+
+        assert!(logger.tail(2).unwrap().is_empty());
+
+        logger.log(LogLevel::Info, "First").unwrap();
+        assert_eq!(logger.tail(2).unwrap(), vec!["[INFO] First"]);
+
+        logger.log(LogLevel::Warning, "Second").unwrap();
+        logger.log(LogLevel::Info, "Third\ncontinued").unwrap();
+        assert_eq!(logger.tail(2).unwrap(), vec!["[INFO] Third", "continued"]);
+
+        for i in 0..1_000 {
+            logger.log(LogLevel::Debug, &format!("Line {}", i)).unwrap();
+        }
+        assert_eq!(
+            logger.tail(2).unwrap(),
+            vec!["[DEBUG] Line 998", "[DEBUG] Line 999"]
+        );
+        assert!(logger.tail(0).unwrap().is_empty());
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_include_pid_and_hostname() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, true);
+        logger.set_include_pid(true);
+        logger.set_include_hostname(true);
+
+        logger.log(LogLevel::Info, "Origin").unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        assert!(contents.starts_with("[INFO] Origin host="));
+        assert!(contents.contains(&format!(" pid={}\n", std::process::id())));
+        assert!(!hostname().is_empty());
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_suspend_resume() {
+        let log_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, true);
+
+        logger.suspend();
+        logger.log(LogLevel::Info, "While suspended").unwrap();
+        logger.resume();
+        logger.log(LogLevel::Info, "After resume").unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        assert_eq!(contents, "[INFO] After resume\n");
+        assert_eq!(logger.counts().info, 1);
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_max_message_len() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, true);
+        logger.set_max_message_len(Some(50));
+
+        let message = "é".repeat(10_000);
+        logger.log(LogLevel::Info, &message).unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        assert_eq!(
+            contents,
+            format!("[INFO] {}{}\n", "é".repeat(50), TRUNCATION_MARKER)
+        );
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_min_level() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, false);
+        logger.set_min_level(LogLevel::Warning);
+
+        logger.log(LogLevel::Debug, "Dropped debug").unwrap();
+        logger.log(LogLevel::Info, "Dropped info").unwrap();
+        logger.log(LogLevel::Warning, "Kept warning").unwrap();
+        logger.log(LogLevel::Error, "Kept error").unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        assert_eq!(contents, "[WARNING] Kept warning\n[ERROR] Kept error\n");
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_header() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, true);
+        logger
+            .set_header(|| format!("# log started pid={}", std::process::id()))
+            .unwrap();
+
+        logger.log(LogLevel::Info, "First message").unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0],
+            format!("# log started pid={}", std::process::id())
+        );
+        assert_eq!(lines[1], "[INFO] First message");
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_set_log_file() {
+        let log_file_a = get_unique_log_filename();
+        let log_file_b = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file_a, true);
+
+        logger.log(LogLevel::Info, "To file A").unwrap();
+        logger.set_log_file(&log_file_b).unwrap();
+        logger.log(LogLevel::Info, "To file B").unwrap();
+
+        assert_eq!(logger.log_file(), log_file_b);
+        assert_eq!(
+            fs::read_to_string(&log_file_a).unwrap(),
+            "[INFO] To file A\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&log_file_b).unwrap(),
+            "[INFO] To file B\n"
+        );
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file_a).unwrap();
+        fs::remove_file(&log_file_b).unwrap();
+    }
+
+    #[test]
+    fn test_logger_log_kv_plain() {
+        let log_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, true);
+
+        logger
+            .log_kv(
+                LogLevel::Info,
+                "user login",
+                &[("user_id", &42), ("ok", &true), ("name", &"alice")],
+            )
+            .unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        assert_eq!(
+            contents,
+            "[INFO] user login user_id=42 ok=true name=alice\n"
+        );
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_log_kv_json() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, true);
+        logger.set_format(LogFormat::Json);
+
+        logger
+            .log_kv(
+                LogLevel::Warning,
+                "slow \"query\"",
+                &[("elapsed_ms", &1.5), ("retry", &false)],
+            )
+            .unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        assert_eq!(
+            contents,
+            "{\"level\":\"WARNING\",\"message\":\"slow \\\"query\\\"\",\"elapsed_ms\":\"1.5\",\"retry\":\"false\"}\n"
+        );
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_log_with_backtrace() {
+        //
+        // The child process logs a message with backtrace capture enabled.
+        //
+        if let Ok(log_file) = std::env::var("LOGGER_BACKTRACE_CHILD") {
+            let logger = Logger::new(&log_file, false);
+            logger
+                .log_with_backtrace(LogLevel::Error, "Crashed")
+                .unwrap();
+            return;
+        }
+
+        let log_file = get_unique_log_filename();
+        let status = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "logger::tests::test_logger_log_with_backtrace",
+                "--exact",
+                "--nocapture",
+            ])
+            .env("LOGGER_BACKTRACE_CHILD", &log_file)
+            .env("RUST_LIB_BACKTRACE", "1")
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let content = fs::read_to_string(&log_file).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines[0], "[ERROR] Crashed");
+        assert!(lines.len() > 1);
+        assert!(lines[1..].iter().all(|line| line.starts_with("    ")));
+        assert!(content.contains("test_logger_log_with_backtrace"));
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_terminate_on_error_exits() {
+        //
+        // The child process logs an error with termination enabled.
+        //
+        if let Ok(log_file) = std::env::var("LOGGER_TERMINATE_CHILD") {
+            let logger = Logger::new(&log_file, true);
+            let _ = logger.log(LogLevel::Error, "Fatal condition");
+            unreachable!("Logger: the process was not terminated.");
+        }
+
+        let log_file = get_unique_log_filename();
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "logger::tests::test_logger_terminate_on_error_exits",
+                "--exact",
+                "--nocapture",
+            ])
+            .env("LOGGER_TERMINATE_CHILD", &log_file)
+            .output()
+            .unwrap();
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(output.status.code(), Some(1));
+        assert!(stderr.contains("Logger: Application terminated abnormally."));
+        assert!(!stderr.contains("panicked"));
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "[ERROR] Fatal condition\n"
+        );
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_error_exit_code() {
+        //
+        // The child process logs an error with a custom exit code.
+        //
+        if let Ok(log_file) = std::env::var("LOGGER_EXIT_CODE_CHILD") {
+            let mut logger = Logger::new(&log_file, true);
+            logger.set_error_exit_code(70);
+            let _ = logger.log(LogLevel::Error, "Fatal condition");
+            unreachable!("Logger: the process was not terminated.");
+        }
+
+        let log_file = get_unique_log_filename();
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "logger::tests::test_logger_error_exit_code",
+                "--exact",
+                "--nocapture",
+            ])
+            .env("LOGGER_EXIT_CODE_CHILD", &log_file)
+            .output()
+            .unwrap();
+
+        assert_eq!(output.status.code(), Some(70));
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_console_symbols_and_colors() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, false);
+        logger.set_console_symbols(Some(LevelSymbols::default()));
+
+        assert_eq!(
+            logger.format_console_line(LogLevel::Warning, "Careful"),
+            "⚠ [WARNING] Careful"
+        );
+
+        logger.set_console_colors(true);
+        assert_eq!(
+            logger.format_console_line(LogLevel::Error, "Broken"),
+            "\x1b[31m✖ [ERROR] Broken\x1b[0m"
+        );
+
+        logger.log(LogLevel::Error, "Broken").unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        assert_eq!(contents, "[ERROR] Broken\n");
+        assert!(!contents.contains('✖'));
+        assert!(!contents.contains('\x1b'));
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_multi_process_lines_not_torn() {
+        const PROCESSES: usize = 4;
+        const LINES: usize = 200;
+        //
+        // Each child process appends its lines to the shared log file.
+        //
+        if let Ok(shared_file) = std::env::var("LOGGER_APPEND_CHILD") {
+            let own_file = get_unique_log_filename();
+            let mut logger = Logger::new(&own_file, false);
+            logger.set_log_file(&shared_file).unwrap();
+            fs::remove_file(&own_file).unwrap();
+
+            let payload = "x".repeat(1_000);
+            for i in 0..LINES {
+                logger
+                    .log(
+                        LogLevel::Info,
+                        &format!("{}:{} {}", std::process::id(), i, payload),
+                    )
+                    .unwrap();
+            }
+            return;
+        }
+
+        let log_file = get_unique_log_filename();
+        let children: Vec<_> = (0..PROCESSES)
+            .map(|_| {
+                std::process::Command::new(std::env::current_exe().unwrap())
+                    .args([
+                        "logger::tests::test_logger_multi_process_lines_not_torn",
+                        "--exact",
+                    ])
+                    .env("LOGGER_APPEND_CHILD", &log_file)
+                    .stdout(std::process::Stdio::null())
+                    .spawn()
+                    .unwrap()
+            })
+            .collect();
+
+        for mut child in children {
+            assert!(child.wait().unwrap().success());
+        }
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        let payload = "x".repeat(1_000);
+        assert_eq!(contents.lines().count(), PROCESSES * LINES);
+
+        for line in contents.lines() {
+            let (prefix, rest) = line.split_once(' ').unwrap();
+            let (_, line_payload) = rest.split_once(' ').unwrap();
+            assert_eq!(prefix, "[INFO]");
+            assert_eq!(line_payload, payload);
+        }
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_short_level_label() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, true);
+        logger.set_level_label(LevelLabel::Short);
+
+        logger.log(LogLevel::Warning, "Abbreviated").unwrap();
+        logger.log(LogLevel::Info, "Unchanged").unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        assert_eq!(contents, "[WARN] Abbreviated\n[INFO] Unchanged\n");
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_log_error_chain() {
+        #[derive(Debug)]
+        struct ChainedError {
+            message: &'static str,
+            source: Option<Box<ChainedError>>,
+        }
+
+        impl std::fmt::Display for ChainedError {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.message)
+            }
+        }
+
+        impl std::error::Error for ChainedError {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                self.source
+                    .as_deref()
+                    .map(|source| source as &(dyn std::error::Error + 'static))
+            }
+        }
+
+        let error = ChainedError {
+            message: "top error",
+            source: Some(Box::new(ChainedError {
+                message: "mid error",
+                source: Some(Box::new(ChainedError {
+                    message: "root error",
+                    source: None,
+                })),
+            })),
+        };
+
+        let log_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, false);
+        logger.log_error_chain(&error).unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        assert_eq!(
+            contents,
+            "[ERROR] top error: caused by: mid error: caused by: root error\n"
+        );
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_deduplicate() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, true);
+        logger.set_deduplicate(true);
+
+        logger.log(LogLevel::Info, "x").unwrap();
+        logger.log(LogLevel::Info, "x").unwrap();
+        logger.log(LogLevel::Info, "x").unwrap();
+        logger.log(LogLevel::Info, "y").unwrap();
+        logger.log(LogLevel::Warning, "y").unwrap();
+        logger.log(LogLevel::Warning, "y").unwrap();
+        logger.sync().unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "[INFO] x",
+                "[INFO] last message repeated 2 times",
+                "[INFO] y",
+                "[WARNING] y",
+                "[WARNING] last message repeated 1 times",
+            ]
+        );
+        assert_eq!(logger.counts().total(), 6);
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_write_snapshot() {
+        let log_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, true);
+        let snapshot = |i: usize| format!("state={} {}\n", i, "x".repeat(10_000));
+
+        logger.write_snapshot(&snapshot(0)).unwrap();
+
+        std::thread::scope(|scope| {
+            let reader = scope.spawn(|| {
+                for _ in 0..200 {
+                    let contents = fs::read_to_string(&log_file).unwrap();
+                    let (state, padding) = contents.split_once(' ').unwrap();
+                    assert!(state.starts_with("state="));
+                    assert_eq!(padding, format!("{}\n", "x".repeat(10_000)));
+                }
+            });
+
+            for i in 1..200 {
+                logger.write_snapshot(&snapshot(i)).unwrap();
+            }
+
+            reader.join().unwrap();
+        });
+
+        assert_eq!(fs::read_to_string(&log_file).unwrap(), snapshot(199));
+
+        logger.log(LogLevel::Info, "After snapshot").unwrap();
+        assert!(
+            fs::read_to_string(&log_file)
+                .unwrap()
+                .ends_with("\n[INFO] After snapshot\n")
+        );
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_on_terminate_hook() {
+        //
+        // The child process logs an error with a termination hook installed.
+        //
+        if let Ok(log_file) = std::env::var("LOGGER_ON_TERMINATE_CHILD") {
+            let mut logger = Logger::new(&log_file, true);
+            logger.set_on_terminate(Box::new(|| eprintln!("on_terminate invoked")));
+            let _ = logger.log(LogLevel::Error, "Fatal condition");
+            unreachable!("Logger: the process was not terminated.");
+        }
+
+        let log_file = get_unique_log_filename();
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "logger::tests::test_logger_on_terminate_hook",
+                "--exact",
+                "--nocapture",
+            ])
+            .env("LOGGER_ON_TERMINATE_CHILD", &log_file)
+            .output()
+            .unwrap();
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(stderr.matches("on_terminate invoked").count(), 1);
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_sub_second_timestamps() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, true);
+        logger.set_clock(FixedClock::new(
+            SystemTime::UNIX_EPOCH + Duration::new(1_735_825_501, 123_456_789),
+        ));
+
+        for (format, digits) in [
+            (TimestampFormat::Rfc3339Millis, 3),
+            (TimestampFormat::Rfc3339Micros, 6),
+        ] {
+            logger.set_timestamp_format(format);
+            let line = logger.format_line(LogLevel::Info, "Precise");
+            let timestamp = line.split(' ').next().unwrap();
+            let fraction = timestamp.split_once('.').unwrap().1.trim_end_matches('Z');
+            assert_eq!(fraction.len(), digits);
+            assert!(fraction.chars().all(|c| c.is_ascii_digit()));
+        }
+
+        logger.log(LogLevel::Info, "Precise").unwrap();
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "2025-01-02T13:45:01.123456Z [INFO] Precise\n"
+        );
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_compact_format() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, true);
+        logger.set_format(LogFormat::Compact);
+
+        logger.log(LogLevel::Info, "Compact line").unwrap();
+        logger.log(LogLevel::Warning, "Low space").unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        assert!(contents.starts_with("I "));
+        assert!(!contents.contains('['));
+        assert_eq!(contents, "I Compact line\nW Low space\n");
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_line_termination_separated() {
+        let (mut logger, log_file) = Logger::temp();
+        logger.set_line_termination(LineTermination::Separated);
+
+        logger.log(LogLevel::Info, "First").unwrap();
+        logger.log(LogLevel::Info, "Second").unwrap();
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "[INFO] First\n[INFO] Second"
+        );
+
+        logger.set_log_file(&log_file).unwrap();
+        logger.log_raw("Third").unwrap();
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "[INFO] First\n[INFO] Second\nThird"
+        );
+    }
+
+    #[test]
+    fn test_logger_current_size() {
+        let (logger, _log_file) = Logger::temp();
+        assert_eq!(logger.current_size().unwrap(), 0);
+
+        logger.log(LogLevel::Info, "Hello").unwrap();
+        assert_eq!(
+            logger.current_size().unwrap(),
+            "[INFO] Hello\n".len() as u64
+        );
+
+        let logger = Logger::from_writer(std::io::sink(), false);
+        assert_eq!(
+            logger.current_size().unwrap_err().kind(),
+            std::io::ErrorKind::Unsupported
+        );
+    }
+
+    #[test]
+    fn test_logger_field_quoting() {
+        let (mut logger, log_file) = Logger::temp();
+        logger.set_quote_values(true);
+        logger
+            .log_kv(
+                LogLevel::Info,
+                "Login",
+                &[("user", &"alice"), ("agent", &"curl 8.0")],
+            )
+            .unwrap();
+        logger.set_field_separator('\t');
+        logger
+            .log_kv(LogLevel::Info, "Logout", &[("user", &"alice")])
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "[INFO] Login user=alice agent=\"curl 8.0\"\n[INFO] Logout\tuser=alice\n"
+        );
+    }
+
+    #[test]
+    fn test_logger_audit() {
+        let (mut logger, log_file) = Logger::temp();
+        logger.set_min_level(LogLevel::Warning);
+        logger.set_flush_every(Some(100));
+        logger.set_sample_rate(LogLevel::Info, 1000);
+        logger.suspend();
+
+        logger.log(LogLevel::Info, "Dropped").unwrap();
+        logger.audit("User alice logged in").unwrap();
+        logger.audit("User alice logged in").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "[INFO] User alice logged in audit=true\n".repeat(2)
+        );
+        assert_eq!(logger.counts().info, 2);
+    }
+
+    #[test]
+    fn test_logger_not_persisted() {
+        struct FailingWriter;
+
+        impl Write for FailingWriter {
+            fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("disk full"))
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let console = SharedBuffer::default();
+        let mut logger = Logger::from_writer(FailingWriter, false);
+        logger.set_console_writer(console.clone());
+
+        assert!(logger.log_raw("Banner").is_err());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            logger.log(LogLevel::Info, "Lost")
+        }));
+        assert!(result.is_err());
+
+        assert_eq!(
+            console.contents(),
+            "Banner [NOT PERSISTED]\n[INFO] Lost [NOT PERSISTED]\n"
+        );
+    }
+
+    #[test]
+    fn test_logger_write_bom() {
+        let (logger, log_file) = Logger::temp();
+        logger.log(LogLevel::Info, "Plain").unwrap();
+        assert!(fs::read(&log_file).unwrap().starts_with(b"[INFO]"));
+
+        let (mut logger, log_file) = Logger::temp();
+        logger.set_write_bom(true).unwrap();
+        logger.set_header(|| "# header".to_string()).unwrap();
+        logger.log(LogLevel::Info, "Marked").unwrap();
+
+        let content = fs::read(&log_file).unwrap();
+        assert_eq!(&content[..3], [0xEF, 0xBB, 0xBF]);
+        assert_eq!(&content[3..], b"# header\n[INFO] Marked\n");
+    }
+
+    #[test]
+    fn test_logger_suppress_terminate() {
+        let (_, log_file) = Logger::temp();
+        let logger = Logger::new(&log_file.to_string_lossy(), true);
+
+        {
+            let _guard = logger.suppress_terminate();
+            assert!(!logger.terminate_on_error());
+            logger.log(LogLevel::Error, "Recovering").unwrap();
+        }
+
+        assert!(logger.terminate_on_error());
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "[ERROR] Recovering\n"
+        );
+    }
+
+    #[test]
+    fn test_logger_binary_format() {
+        let (mut logger, log_file) = Logger::temp();
+        let time = SystemTime::UNIX_EPOCH + Duration::from_millis(1_735_826_399_123);
+        logger.set_format(LogFormat::Binary);
+        logger.set_clock(FixedClock::new(time));
+
+        logger.log(LogLevel::Info, "Started").unwrap();
+        logger
+            .log_kv(LogLevel::Warning, "Slow", &[("ms", &250)])
+            .unwrap();
+        logger.log(LogLevel::Error, "Multi\nline").unwrap();
+
+        let records: Vec<LogRecord> = read_binary_log(&log_file)
+            .unwrap()
+            .collect::<std::io::Result<_>>()
+            .unwrap();
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].level, LogLevel::Info);
+        assert_eq!(records[0].time, time);
+        assert_eq!(records[0].message, "Started");
+        assert_eq!(records[1].level, LogLevel::Warning);
+        assert_eq!(records[1].message, "Slow ms=250");
+        assert_eq!(records[2].level, LogLevel::Error);
+        assert_eq!(records[2].message, "Multi\nline");
+    }
+
+    #[test]
+    fn test_logger_log_raw() {
+        let (mut logger, log_file) = Logger::temp();
+        logger.set_timestamp_format(TimestampFormat::Rfc3339);
+        logger.set_min_level(LogLevel::Error);
+
+        logger.log_raw("==== Banner ====").unwrap();
+
+        assert_eq!(fs::read_to_string(&log_file).unwrap(), "==== Banner ====\n");
+        assert_eq!(logger.counts().total(), 0);
+    }
+
+    #[test]
+    fn test_logger_sample_rate() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, false);
+        logger.set_sample_rate(LogLevel::Info, 10);
+
+        for index in 0..100 {
+            logger
+                .log(LogLevel::Info, &format!("Info {}", index))
+                .unwrap();
+        }
+        logger.log(LogLevel::Error, "Kept").unwrap();
+
+        let content = fs::read_to_string(&log_file).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 11);
+        assert_eq!(lines[0], "[INFO] Info 0");
+        assert_eq!(lines[1], "[INFO] Info 10");
+        assert_eq!(lines[10], "[ERROR] Kept");
+        assert_eq!(logger.counts().info, 10);
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_log_at() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, false);
+        logger.set_timestamp_format(TimestampFormat::Rfc3339Millis);
+
+        let when = SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+        logger.log_at(when, LogLevel::Info, "Replayed").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "2023-11-14T22:13:20.123Z [INFO] Replayed\n"
+        );
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_rotation_period_hourly() {
+        let root = std::env::temp_dir().join(get_unique_log_filename());
+        fs::create_dir(&root).unwrap();
+        let mut logger = Logger::new(&root.join("app.log").to_string_lossy(), false);
+        logger.set_rotation_period(Some(RotationPeriod::Hourly));
+
+        logger.set_clock(FixedClock::new(
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1_735_826_399),
+        ));
+        logger.log(LogLevel::Info, "Before").unwrap();
+        logger.set_clock(FixedClock::new(
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1_735_826_400),
+        ));
+        logger.log(LogLevel::Info, "After").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(root.join("app-2025-01-02-13.log")).unwrap(),
+            "[INFO] Before\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("app-2025-01-02-14.log")).unwrap(),
+            "[INFO] After\n"
+        );
+        assert_eq!(logger.tail(1).unwrap(), vec!["[INFO] After".to_string()]);
+        //
+        // Clean up.
+        //
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_logger_rotation_period_weekly() {
+        let root = std::env::temp_dir().join(get_unique_log_filename());
+        fs::create_dir(&root).unwrap();
+        let mut logger = Logger::new(&root.join("app.log").to_string_lossy(), false);
+        logger.set_rotation_period(Some(RotationPeriod::Weekly));
+        //
+        // Sunday 2025-01-05 is in the first ISO week and Monday 2025-01-06
+        // starts the second one.
+        //
+        logger.set_clock(FixedClock::new(
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1_736_118_000),
+        ));
+        logger.log(LogLevel::Info, "Sunday").unwrap();
+        logger.set_clock(FixedClock::new(
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1_736_121_600),
+        ));
+        logger.log(LogLevel::Info, "Monday").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(root.join("app-2025-W01.log")).unwrap(),
+            "[INFO] Sunday\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("app-2025-W02.log")).unwrap(),
+            "[INFO] Monday\n"
+        );
+        //
+        // Clean up.
+        //
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_logger_rotate() {
+        let log_file = get_unique_log_filename();
+        let path = Path::new(&log_file);
+        let mut logger = Logger::new(&log_file, false);
+        logger.set_max_backups(3);
+
+        for index in 1..=6 {
+            logger
+                .log(LogLevel::Info, &format!("Line {}", index))
+                .unwrap();
+            logger.rotate().unwrap();
+        }
+
+        logger.log(LogLevel::Info, "Line 7").unwrap();
+
+        assert_eq!(fs::read_to_string(path).unwrap(), "[INFO] Line 7\n");
+        for (number, expected) in [(1, 6), (2, 5), (3, 4)] {
+            assert_eq!(
+                fs::read_to_string(backup_path(path, number)).unwrap(),
+                format!("[INFO] Line {}\n", expected)
+            );
+        }
+        assert!(!backup_path(path, 4).exists());
+        //
+        // Clean up.
+        //
+        fs::remove_file(path).unwrap();
+        for number in 1..=3 {
+            fs::remove_file(backup_path(path, number)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_logger_max_file_size() {
+        let log_file = get_unique_log_filename();
+        let path = Path::new(&log_file);
+        let mut logger = Logger::new(&log_file, false);
+        logger.set_max_file_size(Some(20));
+        logger.set_max_backups(1);
+
+        logger.log(LogLevel::Info, "First line").unwrap();
+        logger.log(LogLevel::Info, "Second line").unwrap();
+        logger.log(LogLevel::Info, "Third line").unwrap();
+
+        assert_eq!(fs::read_to_string(path).unwrap(), "[INFO] Third line\n");
+        assert_eq!(
+            fs::read_to_string(backup_path(path, 1)).unwrap(),
+            "[INFO] First line\n[INFO] Second line\n"
+        );
+        //
+        // Clean up.
+        //
+        fs::remove_file(path).unwrap();
+        fs::remove_file(backup_path(path, 1)).unwrap();
+    }
+
+    #[test]
+    fn test_logger_console_format() {
+        let log_file = get_unique_log_filename();
+        let console = SharedBuffer::default();
+        let mut logger = Logger::new(&log_file, false);
+        logger.set_console_writer(console.clone());
+        logger.set_console_format(Some(LogFormat::Json));
+
+        logger
+            .log_kv(LogLevel::Info, "Started", &[("port", &8080)])
+            .unwrap();
+
+        assert_eq!(
+            console.contents(),
+            "{\"level\":\"INFO\",\"message\":\"Started\",\"port\":\"8080\"}\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "[INFO] Started port=8080\n"
+        );
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_log_err() {
+        let log_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, false);
+
+        let ok: Result<u32, String> = logger.log_err(Ok(42), LogLevel::Warning);
+        assert_eq!(ok, Ok(42));
+
+        let err: Result<u32, String> =
+            logger.log_err(Err("disk full".to_string()), LogLevel::Warning);
+        assert_eq!(err, Err("disk full".to_string()));
+
+        let content = fs::read_to_string(&log_file).unwrap();
+        assert_eq!(content, "[WARNING] disk full\n");
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_continuation_prefix() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, false);
+
+        logger.log(LogLevel::Info, "First\nSecond").unwrap();
+        logger.set_continuation_prefix(Some("    | ".to_string()));
+        logger.log(LogLevel::Info, "First\nSecond").unwrap();
+
+        let content = fs::read_to_string(&log_file).unwrap();
+        assert_eq!(
+            content,
+            "[INFO] First\nSecond\n[INFO] First\n    | Second\n"
+        );
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_format_template() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::from_writer(buffer.clone(), false);
+        logger.set_console_writer(std::io::sink());
+        logger.set_tag(Some("db"));
+
+        logger
+            .set_format_template(Some("{level}: {message}{fields} {{{tag}}}"))
+            .unwrap();
+        logger.log(LogLevel::Warning, "Slow query").unwrap();
+
+        match logger.set_format_template(Some("{level} {mesage}")) {
+            Err(LoggerError::InvalidTemplate(unknown)) => assert_eq!(unknown, "{mesage}"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        logger.log(LogLevel::Info, "Kept").unwrap();
+
+        logger.set_format_template(None).unwrap();
+        logger.log(LogLevel::Info, "Fixed").unwrap();
+
+        assert_eq!(
+            buffer.contents(),
+            "WARNING: Slow query {db}\nINFO: Kept {db}\n[INFO] [db] Fixed\n"
+        );
+    }
+
+    #[test]
+    fn test_logger_enabled() {
+        let mut logger = Logger::from_writer(std::io::sink(), false);
+        logger.set_min_level(LogLevel::Warning);
+
+        assert!(!logger.enabled(LogLevel::Debug));
+        assert!(!logger.enabled(LogLevel::Info));
+        assert!(logger.enabled(LogLevel::Warning));
+        assert!(logger.enabled(LogLevel::Error));
+
+        logger.suspend();
+        assert!(!logger.enabled(LogLevel::Error));
+        logger.resume();
+        assert!(logger.enabled(LogLevel::Error));
+    }
+
+    #[test]
+    fn test_logger_sequence_numbers() {
+        let (mut logger, log_file) = Logger::temp();
+        logger.set_sequence_width(Some(4));
+
+        logger.log(LogLevel::Info, "First").unwrap();
+        logger.log(LogLevel::Info, "Second").unwrap();
+        logger.log(LogLevel::Warning, "Third").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "[#0001] [INFO] First\n[#0002] [INFO] Second\n[#0003] [WARNING] Third\n"
+        );
+
+        logger.set_format(LogFormat::Json);
+        logger.log(LogLevel::Info, "Fourth").unwrap();
+        assert_eq!(
+            logger.tail(1).unwrap(),
+            vec!["{\"seq\":4,\"level\":\"INFO\",\"message\":\"Fourth\"}".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_logger_stats() {
+        let (mut logger, _log_file) = Logger::temp();
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        logger.set_clock(FixedClock::new(time));
+        assert_eq!(logger.stats(), LoggerStats::default());
+
+        logger.log(LogLevel::Info, "One").unwrap();
+        logger.log(LogLevel::Warning, "Two").unwrap();
+        logger.log(LogLevel::Debug, "Three").unwrap();
+        logger.log(LogLevel::Info, "Four").unwrap();
+
+        let stats = logger.stats();
+        assert_eq!(stats.total_records, 4);
+        assert_eq!(
+            stats.total_bytes,
+            "[INFO] One\n[WARNING] Two\n[DEBUG] Three\n[INFO] Four\n".len() as u64
+        );
+        assert_eq!(stats.total_bytes, logger.current_size().unwrap());
+        assert_eq!(stats.counts.info, 2);
+        assert_eq!(stats.counts.warning, 1);
+        assert_eq!(stats.counts.debug, 1);
+        assert_eq!(stats.counts.error, 0);
+        assert_eq!(stats.last_record_time, Some(time));
+    }
+
+    #[test]
+    fn test_logger_flush() {
+        let (mut logger, log_file) = Logger::temp();
+        assert!(logger.flush().is_ok());
+
+        logger.log(LogLevel::Info, "Unbuffered").unwrap();
+        assert!(logger.flush().is_ok());
+
+        logger.set_flush_every(Some(10));
+        logger.log(LogLevel::Info, "Buffered").unwrap();
+        assert_eq!(fs::read_to_string(&log_file).unwrap().lines().count(), 1);
+
+        assert!(logger.flush().is_ok());
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "[INFO] Unbuffered\n[INFO] Buffered\n"
+        );
+    }
+
+    #[test]
+    fn test_logger_flush_every() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, false);
+        logger.set_flush_every(Some(3));
+
+        logger.log(LogLevel::Info, "First").unwrap();
+        logger.log(LogLevel::Info, "Second").unwrap();
+        assert!(fs::read_to_string(&log_file).unwrap().is_empty());
+
+        logger.log(LogLevel::Info, "Third").unwrap();
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "[INFO] First\n[INFO] Second\n[INFO] Third\n"
+        );
+
+        logger.log(LogLevel::Info, "Fourth").unwrap();
+        logger.log(LogLevel::Error, "Fifth").unwrap();
+        assert_eq!(fs::read_to_string(&log_file).unwrap().lines().count(), 5);
+
+        logger.log(LogLevel::Info, "Sixth").unwrap();
+        drop(logger);
+        assert_eq!(fs::read_to_string(&log_file).unwrap().lines().count(), 6);
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_flush_policy() {
+        let (mut logger, log_file) = Logger::temp();
+
+        logger.set_flush_policy(FlushPolicy::EveryLine);
+        logger.log(LogLevel::Info, "First").unwrap();
+        assert_eq!(fs::read_to_string(&log_file).unwrap(), "[INFO] First\n");
+
+        logger.set_flush_policy(FlushPolicy::EveryN(2));
+        logger.log(LogLevel::Info, "Second").unwrap();
+        assert_eq!(fs::read_to_string(&log_file).unwrap().lines().count(), 1);
+
+        logger.log(LogLevel::Info, "Third").unwrap();
+        assert_eq!(fs::read_to_string(&log_file).unwrap().lines().count(), 3);
+
+        logger.set_flush_policy(FlushPolicy::Buffered);
+        logger.log(LogLevel::Warning, "Fourth").unwrap();
+        assert_eq!(fs::read_to_string(&log_file).unwrap().lines().count(), 3);
+
+        logger.log(LogLevel::Error, "Fifth").unwrap();
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "[INFO] First\n[INFO] Second\n[INFO] Third\n[WARNING] Fourth\n[ERROR] Fifth\n"
+        );
+    }
+
+    #[test]
+    fn test_logger_flush_policy_interval() {
+        let (mut logger, log_file) = Logger::temp();
+        logger.set_flush_policy(FlushPolicy::Interval(std::time::Duration::from_millis(20)));
+
+        logger.log(LogLevel::Info, "First").unwrap();
+        assert!(fs::read_to_string(&log_file).unwrap().is_empty());
+
+        std::thread::sleep(std::time::Duration::from_millis(25));
+        logger.log(LogLevel::Info, "Second").unwrap();
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "[INFO] First\n[INFO] Second\n"
+        );
+    }
+
+    #[test]
+    fn test_logger_with_verbose() {
+        let (mut logger, log_file) = Logger::temp();
+        logger.set_min_level(LogLevel::Warning);
+
+        logger.log(LogLevel::Debug, "Outside").unwrap();
+        let result = logger.with_verbose(LogLevel::Debug, || {
+            logger.log(LogLevel::Debug, "Inside").unwrap();
+            42
+        });
+        logger.log(LogLevel::Debug, "After").unwrap();
+
+        assert_eq!(result, 42);
+        assert_eq!(fs::read_to_string(&log_file).unwrap(), "[DEBUG] Inside\n");
+        //
+        // The minimum level is restored even if the closure panics.
+        //
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            logger.with_verbose(LogLevel::Debug, || panic!("Failed operation"))
+        }));
+        assert!(panicked.is_err());
+        assert!(!logger.enabled(LogLevel::Debug));
+    }
+
+    #[test]
+    fn test_logger_strips_ansi_from_file() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, false);
+        logger.set_console_colors(true);
+
+        logger
+            .log(LogLevel::Warning, "\x1b[1;33mDisk\x1b[0m almost full")
+            .unwrap();
+        logger.log_raw("\x1b[2m-- separator --\x1b[0m").unwrap();
+        logger.set_format(LogFormat::Json);
+        logger
+            .log_kv(
+                LogLevel::Info,
+                "Mounted",
+                &[("path", &"\x1b[4m/data\x1b[0m")],
+            )
+            .unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        assert!(!contents.contains('\x1b'));
+        assert!(!contents.contains("\\u001b"));
+        assert!(contents.starts_with("[WARNING] Disk almost full\n-- separator --\n"));
+        assert!(contents.contains("\"path\":\"/data\""));
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_shutdown() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, false);
+        logger.set_flush_policy(FlushPolicy::Buffered);
+
+        for number in 0..1000 {
+            logger
+                .log(LogLevel::Info, &format!("Record {}", number))
+                .unwrap();
+        }
+        assert!(fs::read_to_string(&log_file).unwrap().is_empty());
+
+        logger.shutdown(std::time::Duration::from_secs(10)).unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        assert_eq!(contents.lines().count(), 1000);
+        assert!(contents.ends_with("[INFO] Record 999\n"));
+        //
+        // The records logged after the shutdown are dropped.
+        //
+        logger.log(LogLevel::Info, "Too late").unwrap();
+        logger.flush().unwrap();
+        assert_eq!(fs::read_to_string(&log_file).unwrap(), contents);
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_shutdown_timeout() {
+        //
+        // A writer which stalls on flush.
+        //
+        struct StallingWriter;
+
+        impl Write for StallingWriter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                std::thread::sleep(std::time::Duration::from_secs(1));
+                Ok(())
+            }
+        }
+
+        let mut logger = Logger::from_writer(StallingWriter, false);
+        logger.set_flush_policy(FlushPolicy::Buffered);
+        logger.log(LogLevel::Info, "Pending").unwrap();
+
+        match logger.shutdown(std::time::Duration::from_millis(50)) {
+            Err(LoggerError::Io(error)) => {
+                assert_eq!(error.kind(), std::io::ErrorKind::TimedOut)
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_logger_enter_scope() {
+        let (logger, log_file) = Logger::temp();
+
+        logger.log(LogLevel::Info, "Request").unwrap();
+        {
+            let _outer = logger.enter_scope();
+            logger.log(LogLevel::Info, "Query").unwrap();
+            {
+                let _inner = logger.enter_scope();
+                logger.log(LogLevel::Debug, "Row").unwrap();
+            }
+            logger.log(LogLevel::Info, "Done").unwrap();
+        }
+        logger.log(LogLevel::Info, "Response").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "[INFO] Request\n[INFO]   Query\n[DEBUG]     Row\n[INFO]   Done\n[INFO] Response\n"
+        );
+    }
+
+    #[test]
+    fn test_logger_timestamp_levels() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, false);
+        logger.set_timestamp_format(TimestampFormat::Rfc3339);
+        logger.set_timestamp_levels(&[LogLevel::Error]);
+
+        logger.log(LogLevel::Info, "Started").unwrap();
+        logger.log(LogLevel::Error, "Failed").unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines[0], "[INFO] Started");
+        assert!(lines[1].ends_with("Z [ERROR] Failed"));
+        assert!(lines[1].chars().next().unwrap().is_ascii_digit());
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_level_labels_json() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::from_writer(buffer.clone(), false);
+        logger.set_format(LogFormat::Json);
+        logger.set_level_labels(HashMap::from([(LogLevel::Error, "\"E\\".to_string())]));
+
+        logger.log(LogLevel::Error, "Failed").unwrap();
+        assert_eq!(
+            buffer.contents(),
+            "{\"level\":\"\\\"E\\\\\",\"message\":\"Failed\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_logger_module_path_json() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::from_writer(buffer.clone(), false);
+        logger.set_format(LogFormat::Json);
+        logger.set_include_module_path(true);
+
+        logger
+            .log_module("app\\\"db\"", LogLevel::Info, "Connected")
+            .unwrap();
+        assert_eq!(
+            buffer.contents(),
+            "{\"module\":\"app\\\\\\\"db\\\"\",\"level\":\"INFO\",\"message\":\"Connected\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_default_logger() {
+        let default_logger = Logger::default();
+        assert_eq!(default_logger.log_file, "default.log");
+        assert_eq!(default_logger.log_file(), "default.log");
+        assert!(!default_logger.terminate_on_error());
+    }
+
+    #[test]
+    #[should_panic(expected = "I cannot write to the log file")]
+    fn test_write_failure_policy_panic() {
+        let logger = Logger::from_writer(FailingWriter, false);
+
+        logger.log(LogLevel::Info, "Lost").unwrap();
+    }
+
+    #[test]
+    fn test_write_failure_policy_drop() {
+        let mut logger = Logger::from_writer(FailingWriter, false);
+        logger.set_write_failure_policy(WriteFailurePolicy::Drop);
+
+        logger.log(LogLevel::Info, "Lost").unwrap();
+        logger.log(LogLevel::Warning, "Also lost").unwrap();
+
+        assert_eq!(logger.stats().dropped_records, 2);
+    }
+
+    #[test]
+    fn test_write_failure_policy_fallback() {
+        let fallback = get_unique_log_filename();
+        let mut logger = Logger::from_writer(FailingWriter, false);
+        logger.set_write_failure_policy(WriteFailurePolicy::Fallback(PathBuf::from(&fallback)));
+
+        logger.log(LogLevel::Info, "Rescued").unwrap();
+        logger.log(LogLevel::Info, "Written directly").unwrap();
+
+        let content = fs::read_to_string(&fallback).unwrap();
+        assert_eq!(content, "[INFO] Rescued\n[INFO] Written directly\n");
+        assert_eq!(logger.stats().dropped_records, 0);
+        //
+        // Clean up.
+        //
+        fs::remove_file(&fallback).unwrap();
+    }
+
+    #[test]
+    fn test_import() {
+        let log_file = get_unique_log_filename();
+        let other_file = get_unique_log_filename();
+        fs::write(
+            &other_file,
+            "[INFO] Shard one\n[WARNING] \x1b[33mShard two\x1b[0m\n",
+        )
+        .unwrap();
+        let logger = Logger::new(&log_file, false);
+
+        logger.log(LogLevel::Info, "Local").unwrap();
+        let imported = logger.import(Path::new(&other_file)).unwrap();
+
+        assert_eq!(imported, 2);
+        let content = fs::read_to_string(&log_file).unwrap();
+        assert_eq!(
+            content,
+            "[INFO] Local\n[INFO] Shard one\n[WARNING] Shard two\n"
+        );
+        assert!(!content.contains('\x1b'));
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+        fs::remove_file(&other_file).unwrap();
+    }
+
+    #[cfg(feature = "timezone")]
+    #[test]
+    fn test_timezone() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::from_writer(buffer.clone(), false);
+        logger.set_timestamp_format(TimestampFormat::Rfc3339);
+        logger.set_timezone(Some(chrono_tz::Asia::Kolkata));
+
+        let time = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_735_825_501);
+        logger.log_at(time, LogLevel::Info, "Local time").unwrap();
+
+        assert_eq!(
+            buffer.contents(),
+            "2025-01-02T19:15:01+05:30 [INFO] Local time\n"
+        );
+    }
+
+    #[test]
+    fn test_escape_html() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, false);
+        logger.set_escape_html(true);
+
+        logger
+            .log_kv(
+                LogLevel::Info,
+                "<script>alert(1)</script>",
+                &[("tag", &"<b>")],
+            )
+            .unwrap();
+
+        let content = fs::read_to_string(&log_file).unwrap();
+        assert_eq!(
+            content,
+            "[INFO] &lt;script&gt;alert(1)&lt;/script&gt; tag=<b>\n"
+        );
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
 
-        match File::create(&log_file) {
-            Ok(_) => (),
-            Err(error) => {
-                panic!("Logger: I cannot create the log file: {:?}", error)
-            }
-        }
+    #[test]
+    fn test_logger_stdout_only() {
         //
-        // Create and return the Logger.
+        // The child process logs from an empty working directory.
         //
-        Self {
-            log_file,
-            terminate_on_error,
+        if std::env::var("LOGGER_STDOUT_ONLY_CHILD").is_ok() {
+            let logger = Logger::stdout_only(false);
+            logger.log(LogLevel::Info, "Console only").unwrap();
+            logger.flush().unwrap();
+            return;
         }
+
+        let directory = tempfile::tempdir().unwrap();
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "logger::tests::test_logger_stdout_only",
+                "--exact",
+                "--nocapture",
+            ])
+            .env("LOGGER_STDOUT_ONLY_CHILD", "1")
+            .current_dir(directory.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("[INFO] Console only"));
+        assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 0);
     }
 
-    ///
-    /// Log a message.
-    ///
-    /// - Arguments:
-    ///   - `message`: The message to log.
-    ///   - `level`: The level of the message.
-    ///
-    pub fn log(&self, level: LogLevel, message: &str) -> Result<(), std::io::Error> {
+    #[test]
+    fn test_logger_install_panic_hook() {
         //
-        // Error message in case the log file cannot be used.
+        // The child process panics with the hook installed, since the hook
+        // is global to the process.
         //
-        const WRITE_ERROR: &str = "Logger: I cannot write to the log file.";
+        if let Ok(log_file) = std::env::var("LOGGER_PANIC_HOOK_CHILD") {
+            Arc::new(Logger::new(&log_file, true)).install_panic_hook();
+            let result = std::panic::catch_unwind(|| panic!("Boom"));
+            assert!(result.is_err());
+            return;
+        }
+
+        let log_file = get_unique_log_filename();
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "logger::tests::test_logger_install_panic_hook",
+                "--exact",
+                "--nocapture",
+            ])
+            .env("LOGGER_PANIC_HOOK_CHILD", &log_file)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        let content = fs::read_to_string(&log_file).unwrap();
+        assert!(content.starts_with("[ERROR] panicked at src/logger.rs:"));
+        assert!(content.ends_with(": Boom\n"));
         //
-        // Log the message on the screen.
+        // Clean up.
         //
-        println!("[{}] {}", level, message);
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_logger_panic_hook_with_locked_destination() {
         //
-        // Log the message on the log file.
+        // A writer which panics while the destination is locked.
         //
-        let mut log_file = OpenOptions::new()
-            .append(true)
-            .read(false)
-            .create(false)
-            .open(&self.log_file)
-            .unwrap();
+        struct PanickingWriter;
+
+        impl Write for PanickingWriter {
+            fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+                panic!("Broken writer")
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        if std::env::var("LOGGER_PANIC_HOOK_LOCKED_CHILD").is_ok() {
+            let logger = Arc::new(Logger::from_writer(PanickingWriter, false));
+            Arc::clone(&logger).install_panic_hook();
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                logger.log(LogLevel::Info, "Lost")
+            }));
+            assert!(result.is_err());
+            return;
+        }
 
-        let msg = format!("[{}] {}\n", level.to_level_string(), message);
-        log_file.write_all(msg.as_bytes()).expect(WRITE_ERROR);
-        log_file.flush().expect(WRITE_ERROR);
+        let mut child = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "logger::tests::test_logger_panic_hook_with_locked_destination",
+                "--exact",
+                "--nocapture",
+            ])
+            .env("LOGGER_PANIC_HOOK_LOCKED_CHILD", "1")
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
         //
-        // If the level is `Error`, then we need to terminate the application.
+        // A deadlocked child is killed rather than hanging the test run.
         //
-        if level == LogLevel::Error && self.terminate_on_error {
-            panic!("Logger: Application terminated abnormally.");
+        let deadline = Instant::now() + std::time::Duration::from_secs(10);
+
+        while child.try_wait().unwrap().is_none() {
+            if Instant::now() > deadline {
+                child.kill().unwrap();
+                panic!("the panic hook deadlocked");
+            }
+
+            std::thread::sleep(std::time::Duration::from_millis(10));
         }
 
-        Ok(())
-    }
-}
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
 
-//
-// Implementation of the `Default` trait for `Logger`.
-//
-impl Default for Logger {
-    ///
-    /// Create a default `Logger`.
-    ///
-    /// - Returns:
-    ///   - The default `Logger` object.
-    ///
-    fn default() -> Self {
-        Logger::new("default.log", true)
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("[ERROR] panicked at src/logger.rs:"));
+        assert!(stderr.contains(": Broken writer"));
     }
-}
 
-// -------------------------------------------------------------------------------------------------
-//
-// Unit Tests.
-//
-// -------------------------------------------------------------------------------------------------
+    #[test]
+    fn test_logger_tag() {
+        let buffer = SharedBuffer::default();
+        let mut tagged = Logger::from_writer(buffer.clone(), false);
+        tagged.set_tag(Some("db"));
+        let untagged = Logger::from_writer(buffer.clone(), false);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use rand::prelude::*;
-    use std::fs;
-    use std::path::Path;
+        tagged.log(LogLevel::Info, "Connected").unwrap();
+        untagged.log(LogLevel::Info, "Started").unwrap();
+        tagged.set_format(LogFormat::Json);
+        tagged.log(LogLevel::Warning, "Slow query").unwrap();
 
-    //
-    // Helper function to generate unique log filenames for tests
-    //
-    fn get_unique_log_filename() -> String {
-        let mut rng = rand::rng();
-        format!("test_log_{}.log", rng.random::<u64>())
+        assert_eq!(
+            buffer.contents(),
+            "[INFO] [db] Connected\n[INFO] Started\n\
+             {\"level\":\"WARNING\",\"tag\":\"db\",\"message\":\"Slow query\"}\n"
+        );
     }
 
     #[test]
-    fn test_logger_new_creates_file() {
+    fn test_logger_log_to() {
         let log_file = get_unique_log_filename();
-        let _logger = Logger::new(&log_file, true);
-        assert!(Path::new(&log_file).exists());
+        let other_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, false);
+
+        logger.log(LogLevel::Info, "Main").unwrap();
+        logger
+            .log_to(Path::new(&other_file), LogLevel::Warning, "Set aside")
+            .unwrap();
+
+        assert_eq!(fs::read_to_string(&log_file).unwrap(), "[INFO] Main\n");
+        assert_eq!(
+            fs::read_to_string(&other_file).unwrap(),
+            "[WARNING] Set aside\n"
+        );
         //
         // Clean up.
         //
         fs::remove_file(&log_file).unwrap();
+        fs::remove_file(&other_file).unwrap();
     }
 
     #[test]
-    #[should_panic(expected = "Logger: I cannot create the log file")]
-    fn test_logger_new_invalid_path() {
-        let invalid_path = "/nonexistent/directory/test.log";
-        let _logger = Logger::new(invalid_path, true);
+    fn test_logger_log_to_write_failure() {
+        let directory = tempfile::tempdir().unwrap();
+        let console = SharedBuffer::default();
+        let mut logger = Logger::from_writer(std::io::sink(), false);
+        logger.set_console_writer(console.clone());
+        logger.set_write_failure_policy(WriteFailurePolicy::Drop);
+
+        logger
+            .log_to(directory.path(), LogLevel::Warning, "Lost")
+            .unwrap();
+
+        assert_eq!(console.contents(), "[WARNING] Lost [NOT PERSISTED]\n");
+        assert_eq!(logger.stats().dropped_records, 1);
+        assert_eq!(logger.counts().total(), 1);
     }
 
     #[test]
-    fn test_logger_log_info_message() {
-        let log_file = get_unique_log_filename();
-        let logger = Logger::new(&log_file, true);
-
-        logger.log(LogLevel::Info, "Test info message").unwrap();
+    fn test_logger_log_to_terminate_on_error() {
+        //
+        // The child process logs an error to another file with termination
+        // enabled.
+        //
+        if let Ok(other_file) = std::env::var("LOGGER_LOG_TO_TERMINATE_CHILD") {
+            let logger = Logger::from_writer(std::io::sink(), true);
+            let _ = logger.log_to(Path::new(&other_file), LogLevel::Error, "Fatal condition");
+            unreachable!("Logger: the process was not terminated.");
+        }
 
-        let mut file = File::open(&log_file).unwrap();
-        let mut contents = String::new();
-        file.read_to_string(&mut contents).unwrap();
+        let other_file = get_unique_log_filename();
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "logger::tests::test_logger_log_to_terminate_on_error",
+                "--exact",
+                "--nocapture",
+            ])
+            .env("LOGGER_LOG_TO_TERMINATE_CHILD", &other_file)
+            .output()
+            .unwrap();
 
-        assert!(contents.contains("[INFO] Test info message"));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(output.status.code(), Some(1));
+        assert!(stderr.contains("Logger: Application terminated abnormally."));
+        assert!(!stderr.contains("panicked"));
+        assert_eq!(
+            fs::read_to_string(&other_file).unwrap(),
+            "[ERROR] Fatal condition\n"
+        );
         //
         // Clean up.
         //
-        fs::remove_file(&log_file).unwrap();
+        fs::remove_file(&other_file).unwrap();
     }
 
     #[test]
-    fn test_logger_log_warning_message() {
-        let log_file = get_unique_log_filename();
-        let logger = Logger::new(&log_file, true);
+    fn test_logger_try_new_path_is_directory() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().to_string_lossy().to_string();
 
-        logger
-            .log(LogLevel::Warning, "Test warning message")
-            .unwrap();
+        assert!(matches!(
+            Logger::try_new(&path, false),
+            Err(LoggerError::PathIsDirectory(error_path)) if error_path == path
+        ));
+    }
 
-        let mut file = File::open(&log_file).unwrap();
-        let mut contents = String::new();
-        file.read_to_string(&mut contents).unwrap();
+    #[test]
+    fn test_logger_flush_console() {
+        //
+        // A console writer which counts its flushes.
+        //
+        #[derive(Clone, Default)]
+        struct FlushCounter(Arc<AtomicU64>);
 
-        assert!(contents.contains("[WARNING] Test warning message"));
+        impl Write for FlushCounter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                self.0.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            }
+        }
+
+        let counter = FlushCounter::default();
+        let mut logger = Logger::from_writer(std::io::sink(), false);
+        logger.set_console_writer(counter.clone());
+
+        logger.log(LogLevel::Info, "Flushed").unwrap();
+        logger.log(LogLevel::Info, "Flushed again").unwrap();
+        assert_eq!(counter.0.load(Ordering::Relaxed), 2);
+
+        logger.set_flush_console(false);
+        logger.log(LogLevel::Info, "Not flushed").unwrap();
+        assert_eq!(counter.0.load(Ordering::Relaxed), 2);
         //
-        // Clean up.
+        // Flushing the standard output does not fail either.
         //
-        fs::remove_file(&log_file).unwrap();
+        let logger = Logger::stdout_only(false);
+        logger.log(LogLevel::Info, "Flushed to stdout").unwrap();
     }
 
     #[test]
-    fn test_logger_log_debug_message() {
-        let log_file = get_unique_log_filename();
-        let logger = Logger::new(&log_file, true);
+    fn test_logger_log_batch() {
+        //
+        // A writer which keeps its contents and counts its flushes.
+        //
+        #[derive(Clone, Default)]
+        struct CountingWriter(SharedBuffer, Arc<AtomicU64>);
 
-        logger.log(LogLevel::Debug, "Test debug message").unwrap();
+        impl Write for CountingWriter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.write(buf)
+            }
 
-        let mut file = File::open(&log_file).unwrap();
-        let mut contents = String::new();
-        file.read_to_string(&mut contents).unwrap();
+            fn flush(&mut self) -> std::io::Result<()> {
+                self.1.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            }
+        }
 
-        assert!(contents.contains("[DEBUG] Test debug message"));
-        //
-        // Clean up.
-        //
-        fs::remove_file(&log_file).unwrap();
+        let writer = CountingWriter::default();
+        let mut logger = Logger::from_writer(writer.clone(), false);
+        logger.set_min_level(LogLevel::Info);
+        let flushes = writer.1.load(Ordering::Relaxed);
+
+        logger
+            .log_batch(&[
+                (LogLevel::Info, "One"),
+                (LogLevel::Debug, "Filtered"),
+                (LogLevel::Warning, "Two"),
+                (LogLevel::Info, "Three"),
+                (LogLevel::Error, "Four"),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            writer.0.contents(),
+            "[INFO] One\n[WARNING] Two\n[INFO] Three\n[ERROR] Four\n"
+        );
+        assert_eq!(writer.1.load(Ordering::Relaxed), flushes + 1);
+        assert_eq!(logger.counts().total(), 4);
     }
 
     #[test]
-    fn test_logger_log_error_message() {
-        let log_file = get_unique_log_filename();
-        let logger = Logger::new(&log_file, false);
+    #[should_panic(expected = "I cannot write to the log file")]
+    fn test_logger_log_batch_write_failure_panic() {
+        let logger = Logger::from_writer(FailingWriter, false);
 
-        logger.log(LogLevel::Error, "Test error message").unwrap();
+        let _ = logger.log_batch(&[(LogLevel::Info, "Lost"), (LogLevel::Info, "Also lost")]);
+    }
 
-        let mut file = File::open(&log_file).unwrap();
-        let mut contents = String::new();
-        file.read_to_string(&mut contents).unwrap();
+    #[test]
+    fn test_logger_log_batch_write_failure_drop() {
+        let mut logger = Logger::from_writer(FailingWriter, false);
+        logger.set_write_failure_policy(WriteFailurePolicy::Drop);
 
-        assert!(contents.contains("[ERROR] Test error message"));
+        logger
+            .log_batch(&[(LogLevel::Info, "Lost"), (LogLevel::Warning, "Also lost")])
+            .unwrap();
+
+        assert_eq!(logger.stats().dropped_records, 1);
+        assert_eq!(logger.counts().total(), 2);
+    }
+
+    #[test]
+    fn test_logger_log_batch_terminates_on_write_failure() {
         //
-        // Clean up.
+        // The child process logs a batch with an error to a failing writer
+        // with termination enabled.
         //
-        fs::remove_file(&log_file).unwrap();
+        if std::env::var("LOGGER_BATCH_TERMINATE_CHILD").is_ok() {
+            let logger = Logger::from_writer(FailingWriter, true);
+            let _ = logger.log_batch(&[(LogLevel::Info, "Lost"), (LogLevel::Error, "Fatal")]);
+            unreachable!("Logger: the process was not terminated.");
+        }
+
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "logger::tests::test_logger_log_batch_terminates_on_write_failure",
+                "--exact",
+                "--nocapture",
+            ])
+            .env("LOGGER_BATCH_TERMINATE_CHILD", "1")
+            .output()
+            .unwrap();
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(output.status.code(), Some(1));
+        assert!(stderr.contains("Logger: Application terminated abnormally."));
+        assert!(!stderr.contains("panicked"));
     }
 
     #[test]
-    fn test_logger_multiple_messages() {
-        let log_file = get_unique_log_filename();
-        let logger = Logger::new(&log_file, true);
-
-        logger.log(LogLevel::Info, "First message").unwrap();
-        logger.log(LogLevel::Warning, "Second message").unwrap();
-        logger.log(LogLevel::Debug, "Third message").unwrap();
+    fn test_logger_open_with_max_age() {
+        let old_file = get_unique_log_filename();
+        let recent_file = get_unique_log_filename();
+        let max_age = std::time::Duration::from_secs(3_600);
+        fs::write(&old_file, "[INFO] Old run\n").unwrap();
+        fs::write(&recent_file, "[INFO] Recent run\n").unwrap();
+        //
+        // Move the modification time of the old file two hours back.
+        //
+        File::options()
+            .write(true)
+            .open(&old_file)
+            .unwrap()
+            .set_modified(SystemTime::now() - 2 * max_age)
+            .unwrap();
 
-        let mut file = File::open(&log_file).unwrap();
-        let mut contents = String::new();
-        file.read_to_string(&mut contents).unwrap();
+        let old = Logger::open_with_max_age(&old_file, max_age, false).unwrap();
+        let recent = Logger::open_with_max_age(&recent_file, max_age, false).unwrap();
+        old.log(LogLevel::Info, "New run").unwrap();
+        recent.log(LogLevel::Info, "New run").unwrap();
 
-        let lines: Vec<&str> = contents.lines().collect();
-        assert_eq!(lines.len(), 3);
-        assert!(contents.contains("[INFO] First message"));
-        assert!(contents.contains("[WARNING] Second message"));
-        assert!(contents.contains("[DEBUG] Third message"));
+        assert_eq!(fs::read_to_string(&old_file).unwrap(), "[INFO] New run\n");
+        assert_eq!(
+            fs::read_to_string(&recent_file).unwrap(),
+            "[INFO] Recent run\n[INFO] New run\n"
+        );
         //
         // Clean up.
         //
-        fs::remove_file(&log_file).unwrap();
+        fs::remove_file(&old_file).unwrap();
+        fs::remove_file(&recent_file).unwrap();
     }
 
     #[test]
-    fn test_logger_multiline_message() {
-        let log_file = get_unique_log_filename();
-        let logger = Logger::new(&log_file, true);
+    fn test_logger_preview() {
+        let mut logger = Logger::from_writer(std::io::sink(), false);
+        logger.set_min_level(LogLevel::Info);
+        logger.set_tag(Some("db"));
 
-        logger.log(LogLevel::Info, "Line 1\nLine 2").unwrap();
+        assert_eq!(
+            logger.preview(),
+            vec![
+                (LogLevel::Info, "[INFO] [db] Sample message".to_string()),
+                (
+                    LogLevel::Warning,
+                    "[WARNING] [db] Sample message".to_string()
+                ),
+                (LogLevel::Error, "[ERROR] [db] Sample message".to_string()),
+            ]
+        );
 
-        let mut file = File::open(&log_file).unwrap();
-        let mut contents = String::new();
-        file.read_to_string(&mut contents).unwrap();
+        logger.set_format(LogFormat::Compact);
+        assert_eq!(logger.preview()[0].1, "I [db] Sample message");
+        assert_eq!(logger.stats().total_bytes, 0);
+    }
 
-        assert!(contents.contains("[INFO] Line 1\nLine 2"));
-        //
-        // Clean up.
-        //
-        fs::remove_file(&log_file).unwrap();
+    #[test]
+    fn test_logger_try_new_with_dirs() {
+        let directory = tempfile::tempdir().unwrap();
+        let log_file = directory.path().join("sub").join("sub2").join("x.log");
+        let log_file = log_file.to_str().unwrap();
+
+        assert!(Logger::try_new(log_file, false).is_err());
+
+        let logger = Logger::try_new_with_dirs(log_file, false).unwrap();
+        logger.log(LogLevel::Info, "Created").unwrap();
+
+        assert!(directory.path().join("sub").join("sub2").is_dir());
+        assert_eq!(fs::read_to_string(log_file).unwrap(), "[INFO] Created\n");
     }
 
     #[test]
-    fn test_logger_special_characters() {
+    fn test_logger_try_new_lazy() {
         let log_file = get_unique_log_filename();
-        let logger = Logger::new(&log_file, true);
-
-        logger
-            .log(LogLevel::Info, "Special chars: !@#$%^&*()")
-            .unwrap();
+        let logger = Logger::try_new_lazy(&log_file, false).unwrap();
 
-        let mut file = File::open(&log_file).unwrap();
-        let mut contents = String::new();
-        file.read_to_string(&mut contents).unwrap();
+        assert!(!Path::new(&log_file).exists());
+        logger.flush().unwrap();
+        assert!(!Path::new(&log_file).exists());
 
-        assert!(contents.contains("[INFO] Special chars: !@#$%^&*()"));
+        logger.log(LogLevel::Info, "First record").unwrap();
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "[INFO] First record\n"
+        );
         //
         // Clean up.
         //
@@ -304,44 +6047,107 @@ mod tests {
     }
 
     #[test]
-    fn test_logger_clone() {
-        let log_file = get_unique_log_filename();
-        let logger = Logger::new(&log_file, true);
-        let logger_clone = logger.clone();
+    fn test_logger_spool() {
+        let directory = get_unique_log_filename().replace(".log", "_spool");
+        let mut logger = Logger::spool(&directory, false).unwrap();
+        logger.set_flush_every(Some(10));
 
-        logger.log(LogLevel::Info, "Original logger").unwrap();
-        logger_clone
-            .log(LogLevel::Warning, "Cloned logger")
-            .unwrap();
+        logger.log(LogLevel::Info, "First").unwrap();
+        logger.log(LogLevel::Warning, "Second").unwrap();
+        logger.log(LogLevel::Error, "Third").unwrap();
 
-        let mut file = File::open(&log_file).unwrap();
-        let mut contents = String::new();
-        file.read_to_string(&mut contents).unwrap();
+        let mut files: Vec<String> = fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        files.sort();
+        assert_eq!(files, ["000001.log", "000002.log", "000003.log"]);
 
-        assert!(contents.contains("[INFO] Original logger"));
-        assert!(contents.contains("[WARNING] Cloned logger"));
+        let spooled = Path::new(&directory);
+        assert_eq!(
+            fs::read_to_string(spooled.join("000001.log")).unwrap(),
+            "[INFO] First\n"
+        );
+        assert_eq!(
+            fs::read_to_string(spooled.join("000002.log")).unwrap(),
+            "[WARNING] Second\n"
+        );
+        assert_eq!(
+            fs::read_to_string(spooled.join("000003.log")).unwrap(),
+            "[ERROR] Third\n"
+        );
+        //
+        // A new logger skips the files which are already spooled.
+        //
+        let logger = Logger::spool(&directory, false).unwrap();
+        logger.log(LogLevel::Info, "Restarted").unwrap();
+        assert_eq!(
+            fs::read_to_string(spooled.join("000004.log")).unwrap(),
+            "[INFO] Restarted\n"
+        );
         //
         // Clean up.
         //
-        fs::remove_file(&log_file).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
     }
 
     #[test]
-    fn test_logger_debug_format() {
-        let log_file = get_unique_log_filename();
-        let logger = Logger::new(&log_file, true);
+    fn test_logger_spool_batch_and_import() {
+        let directory = get_unique_log_filename().replace(".log", "_spool");
+        let other_file = get_unique_log_filename();
+        fs::write(&other_file, "[INFO] Shard one\n[WARNING] Shard two\n").unwrap();
+        let logger = Logger::spool(&directory, false).unwrap();
 
-        let debug_output = format!("{:?}", logger);
-        assert!(debug_output.contains(&log_file));
+        logger
+            .log_batch(&[(LogLevel::Info, "First"), (LogLevel::Warning, "Second")])
+            .unwrap();
+        assert_eq!(logger.import(Path::new(&other_file)).unwrap(), 2);
+
+        let spooled = Path::new(&directory);
+        assert_eq!(fs::read_dir(spooled).unwrap().count(), 4);
+        assert_eq!(
+            fs::read_to_string(spooled.join("000001.log")).unwrap(),
+            "[INFO] First\n"
+        );
+        assert_eq!(
+            fs::read_to_string(spooled.join("000002.log")).unwrap(),
+            "[WARNING] Second\n"
+        );
+        assert_eq!(
+            fs::read_to_string(spooled.join("000003.log")).unwrap(),
+            "[INFO] Shard one\n"
+        );
+        assert_eq!(
+            fs::read_to_string(spooled.join("000004.log")).unwrap(),
+            "[WARNING] Shard two\n"
+        );
         //
         // Clean up.
         //
-        fs::remove_file(&log_file).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+        fs::remove_file(&other_file).unwrap();
     }
 
     #[test]
-    fn test_default_logger() {
-        let default_logger = Logger::default();
-        assert_eq!(default_logger.log_file, "default.log");
+    fn test_logger_include_delta() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::from_writer(buffer.clone(), false);
+        logger.set_include_delta(true);
+
+        logger.log(LogLevel::Info, "First").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        logger.log(LogLevel::Info, "Second").unwrap();
+
+        let contents = buffer.contents();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines[0], "[+0.0ms] [INFO] First");
+        assert!(lines[1].ends_with(" [INFO] Second"));
+
+        let delta = lines[1]
+            .strip_prefix("[+")
+            .and_then(|line| line.split_once("ms]"))
+            .map(|(delta, _)| delta.parse::<f64>().unwrap())
+            .unwrap();
+        assert!(delta >= 20.0);
     }
 }
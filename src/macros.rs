@@ -0,0 +1,185 @@
+// -------------------------------------------------------------------------------------------------
+//
+//  Implementation of a general purpose logger.
+//
+//  Copyright (c) 2025 by Dr. Panos Asproulis (p.asproulis@icloud.com).
+//  All Rights Reserved.
+//
+// -------------------------------------------------------------------------------------------------
+
+//! Implementation of the logging macros.
+//!
+//! The macros format their arguments like `format!` and log the result
+//! through a `Logger`. Levels below the compile-time maximum level, selected
+//! through the `max_level_*` cargo features, expand to a constant `Ok(())`
+//! so they have no runtime cost.
+
+///
+/// Log a formatted message at the specified level.
+///
+/// - Arguments:
+///   - `logger`: The `Logger` to use.
+///   - `level`: The `LogLevel` of the message.
+///   - The format string and its arguments.
+///
+/// - Returns:
+///   - The result of `Logger::log`, or `Ok(())` if the level is compiled out.
+///
+/// The module path of the caller is passed along and included in the line
+/// if enabled through `Logger::set_include_module_path`.
+///
+#[macro_export]
+macro_rules! log_at_level {
+    ($logger:expr, $level:expr, $($arg:tt)+) => {{
+        let level: $crate::LogLevel = $level;
+        if level.is_statically_enabled() {
+            $logger.log_module(::std::module_path!(), level, &::std::format!($($arg)+))
+        } else {
+            ::std::result::Result::<(), $crate::LoggerError>::Ok(())
+        }
+    }};
+}
+
+///
+/// Log a formatted `Debug` message.
+///
+#[macro_export]
+macro_rules! log_debug {
+    ($logger:expr, $($arg:tt)+) => {
+        $crate::log_at_level!($logger, $crate::LogLevel::Debug, $($arg)+)
+    };
+}
+
+///
+/// Log a formatted `Info` message.
+///
+#[macro_export]
+macro_rules! log_info {
+    ($logger:expr, $($arg:tt)+) => {
+        $crate::log_at_level!($logger, $crate::LogLevel::Info, $($arg)+)
+    };
+}
+
+///
+/// Log a formatted `Warning` message.
+///
+#[macro_export]
+macro_rules! log_warning {
+    ($logger:expr, $($arg:tt)+) => {
+        $crate::log_at_level!($logger, $crate::LogLevel::Warning, $($arg)+)
+    };
+}
+
+///
+/// Log a formatted `Error` message.
+///
+#[macro_export]
+macro_rules! log_error {
+    ($logger:expr, $($arg:tt)+) => {
+        $crate::log_at_level!($logger, $crate::LogLevel::Error, $($arg)+)
+    };
+}
+
+// -------------------------------------------------------------------------------------------------
+//
+// Unit Tests.
+//
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::Logger;
+    use rand::prelude::*;
+    use std::fs;
+
+    //
+    // Helper function to generate unique log filenames for tests
+    //
+    fn get_unique_log_filename() -> String {
+        let mut rng = rand::rng();
+        format!("test_log_{}.log", rng.random::<u64>())
+    }
+
+    #[test]
+    fn test_log_macros() {
+        let log_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, false);
+
+        log_warning!(logger, "Disk at {}%", 91).unwrap();
+        log_error!(logger, "Failed: {}", "timeout").unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+
+        if cfg!(feature = "max_level_error") {
+            assert_eq!(contents, "[ERROR] Failed: timeout\n");
+        } else {
+            assert_eq!(contents, "[WARNING] Disk at 91%\n[ERROR] Failed: timeout\n");
+        }
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_log_debug_respects_static_max_level() {
+        let log_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, false);
+
+        log_debug!(logger, "Debug value {}", 42).unwrap();
+        log_info!(logger, "Info value {}", 7).unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+
+        if cfg!(any(
+            feature = "max_level_info",
+            feature = "max_level_warning",
+            feature = "max_level_error"
+        )) {
+            assert!(!contents.contains("[DEBUG]"));
+        } else {
+            assert!(contents.contains("[DEBUG] Debug value 42"));
+        }
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+
+    #[test]
+    fn test_log_macros_module_path() {
+        mod db {
+            pub fn connect(logger: &crate::Logger) {
+                log_info!(logger, "connected").unwrap();
+            }
+        }
+
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, false);
+
+        db::connect(&logger);
+        logger.set_include_module_path(true);
+        db::connect(&logger);
+        logger.log(crate::LogLevel::Info, "no module").unwrap();
+
+        let contents = fs::read_to_string(&log_file).unwrap();
+
+        if cfg!(any(
+            feature = "max_level_warning",
+            feature = "max_level_error"
+        )) {
+            assert_eq!(contents, "[INFO] no module\n");
+        } else {
+            assert_eq!(
+                contents,
+                "[INFO] connected\n\
+                 [logger::macros::tests::db] [INFO] connected\n\
+                 [INFO] no module\n"
+            );
+        }
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+}
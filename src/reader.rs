@@ -0,0 +1,326 @@
+// -------------------------------------------------------------------------------------------------
+//
+//  Implementation of a general purpose logger.
+//
+//  Copyright (c) 2025 by Dr. Panos Asproulis (p.asproulis@icloud.com).
+//  All Rights Reserved.
+//
+// -------------------------------------------------------------------------------------------------
+
+//! Implementation of the parsing of the log files written in the plain
+//! format, optionally prefixed with a sequence number and an RFC 3339 or
+//! uptime timestamp.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use crate::levels::LogLevel;
+use crate::timestamp::days_from_civil;
+
+///
+/// A record parsed from a plain log line: its level, its wall-clock time if
+/// the line carries an RFC 3339 timestamp, and its message including any
+/// fields.
+///
+pub type ParsedRecord = (LogLevel, Option<SystemTime>, String);
+
+///
+/// Parse a line written in the plain format, such as `[INFO] Started` or
+/// `2025-01-02T13:45:01Z [WARNING] Disk almost full`.
+///
+/// - Arguments:
+///   - `line`: The line to parse, with or without its line ending.
+///
+/// - Returns:
+///   - The parsed record, or `None` if the line is not in the plain format.
+///
+pub fn parse_log_line(line: &str) -> Option<ParsedRecord> {
+    let mut rest = line.trim_end_matches(['\r', '\n']);
+    //
+    // Skip the sequence number, if any.
+    //
+    if rest.starts_with("[#") {
+        rest = rest.split_once("] ")?.1;
+    }
+    //
+    // Parse the wall-clock timestamp, or skip the uptime, if any.
+    //
+    let mut time = None;
+
+    if !rest.starts_with('[') || rest.starts_with("[+") {
+        let (timestamp, remainder) = rest.split_once(' ')?;
+
+        if !timestamp.starts_with("[+") {
+            time = Some(parse_rfc3339(timestamp)?);
+        }
+
+        rest = remainder;
+    }
+
+    let rest = rest.strip_prefix('[')?;
+    let (label, message) = rest
+        .split_once("] ")
+        .or_else(|| rest.strip_suffix(']').map(|label| (label, "")))?;
+    let level = match label {
+        "WARN" => LogLevel::Warning,
+        label => label.parse().ok()?,
+    };
+
+    Some((level, time, message.to_string()))
+}
+
+///
+/// Parse a timestamp in RFC 3339 format, such as `2025-01-02T13:45:01Z`,
+/// `2025-01-02T13:45:01.123Z` or `2025-01-02T08:45:01-05:00`.
+///
+/// - Arguments:
+///   - `timestamp`: The timestamp to parse.
+///
+/// - Returns:
+///   - The parsed time, or `None` if the timestamp is invalid or precedes
+///     the Unix epoch.
+///
+fn parse_rfc3339(timestamp: &str) -> Option<SystemTime> {
+    let bytes = timestamp.as_bytes();
+
+    if bytes.len() < 20
+        || bytes[4] != b'-'
+        || bytes[7] != b'-'
+        || bytes[10] != b'T'
+        || bytes[13] != b':'
+        || bytes[16] != b':'
+    {
+        return None;
+    }
+
+    let number = |range: std::ops::Range<usize>| -> Option<i64> {
+        let digits = timestamp.get(range)?;
+        digits
+            .bytes()
+            .all(|byte| byte.is_ascii_digit())
+            .then(|| digits.parse().ok())?
+    };
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    let (hour, minute, second) = (number(11..13)?, number(14..16)?, number(17..19)?);
+
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 {
+        return None;
+    }
+    //
+    // Parse the fraction of the second and the UTC offset.
+    //
+    let mut rest = &timestamp[19..];
+    let mut nanos = 0;
+
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.len()
+            - fraction
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .len();
+
+        if digits == 0 || digits > 9 {
+            return None;
+        }
+
+        nanos = fraction[..digits].parse::<u32>().ok()? * 10_u32.pow(9 - digits as u32);
+        rest = &fraction[digits..];
+    }
+
+    let offset = match rest {
+        "Z" => 0,
+        _ => {
+            let sign = match rest.get(..1)? {
+                "+" => 1,
+                "-" => -1,
+                _ => return None,
+            };
+            let (hours, minutes) = rest[1..].split_once(':')?;
+
+            if hours.len() != 2 || minutes.len() != 2 {
+                return None;
+            }
+
+            sign * (hours.parse::<i64>().ok()? * 3_600 + minutes.parse::<i64>().ok()? * 60)
+        }
+    };
+
+    let days = days_from_civil(year, month as u32, day as u32);
+    let seconds = days * 86_400 + hour * 3_600 + minute * 60 + second - offset;
+
+    Some(SystemTime::UNIX_EPOCH + Duration::new(u64::try_from(seconds).ok()?, nanos))
+}
+
+///
+/// An iterator over the records of a plain log, which skips the lines that
+/// cannot be parsed, such as the continuation lines of a multi-line message.
+///
+#[derive(Debug)]
+pub struct PlainLogReader<R: BufRead> {
+    ///
+    /// The reader of the plain log.
+    ///
+    reader: R,
+}
+
+//
+// Implementation of the `PlainLogReader` struct.
+//
+impl<R: BufRead> PlainLogReader<R> {
+    ///
+    /// Create a new `PlainLogReader`.
+    ///
+    /// - Arguments:
+    ///   - `reader`: The reader of the plain log.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `PlainLogReader` object.
+    ///
+    pub fn new(reader: R) -> Self {
+        Self { reader }
+    }
+}
+
+//
+// Implementation of the `Iterator` trait for `PlainLogReader`.
+//
+impl<R: BufRead> Iterator for PlainLogReader<R> {
+    type Item = std::io::Result<ParsedRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut line = String::new();
+
+        loop {
+            line.clear();
+
+            match self.reader.read_line(&mut line) {
+                Ok(0) => return None,
+                Ok(_) => {
+                    if let Some(record) = parse_log_line(&line) {
+                        return Some(Ok(record));
+                    }
+                }
+                Err(error) => return Some(Err(error)),
+            }
+        }
+    }
+}
+
+///
+/// Open a log file written in the plain format for reading.
+///
+/// - Arguments:
+///   - `path`: The path of the log file.
+///
+/// - Returns:
+///   - An iterator over the parsed records of the file, or the error which
+///     occurred while opening it.
+///
+pub fn read_log(path: impl AsRef<Path>) -> std::io::Result<PlainLogReader<BufReader<File>>> {
+    Ok(PlainLogReader::new(BufReader::new(File::open(path)?)))
+}
+
+// -------------------------------------------------------------------------------------------------
+//
+// Unit Tests.
+//
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logger::Logger;
+    use crate::timestamp::TimestampFormat;
+    use rand::prelude::*;
+    use std::fs;
+
+    //
+    // Helper function to generate unique log filenames for tests
+    //
+    fn get_unique_log_filename() -> String {
+        let mut rng = rand::rng();
+        format!("test_log_{}.log", rng.random::<u64>())
+    }
+
+    #[test]
+    fn test_parse_log_line_levels() {
+        for level in LogLevel::all() {
+            let line = format!("[{}] Message with key=value\n", level);
+            assert_eq!(
+                parse_log_line(&line),
+                Some((*level, None, "Message with key=value".to_string()))
+            );
+        }
+        assert_eq!(
+            parse_log_line("[WARN] Short label"),
+            Some((LogLevel::Warning, None, "Short label".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_log_line_timestamps() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_735_825_501);
+
+        assert_eq!(
+            parse_log_line("2025-01-02T13:45:01Z [INFO] Started"),
+            Some((LogLevel::Info, Some(time), "Started".to_string()))
+        );
+        assert_eq!(
+            parse_log_line("[#0007] 2025-01-02T13:45:01.250Z [ERROR] Failed"),
+            Some((
+                LogLevel::Error,
+                Some(time + Duration::from_millis(250)),
+                "Failed".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_log_line("2025-01-02T08:45:01-05:00 [DEBUG] Offset"),
+            Some((LogLevel::Debug, Some(time), "Offset".to_string()))
+        );
+        assert_eq!(
+            parse_log_line("[+12.345s] [INFO] Uptime"),
+            Some((LogLevel::Info, None, "Uptime".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_log_line_malformed() {
+        assert_eq!(parse_log_line(""), None);
+        assert_eq!(parse_log_line("    continuation line"), None);
+        assert_eq!(parse_log_line("[LOUD] Unknown level"), None);
+        assert_eq!(
+            parse_log_line("2025-13-02T13:45:01Z [INFO] Bad month"),
+            None
+        );
+        assert_eq!(parse_log_line("yesterday [INFO] Bad timestamp"), None);
+    }
+
+    #[test]
+    fn test_read_log() {
+        let log_file = get_unique_log_filename();
+        let mut logger = Logger::new(&log_file, false);
+        logger.set_timestamp_format(TimestampFormat::Rfc3339Millis);
+
+        logger.log(LogLevel::Info, "First").unwrap();
+        logger
+            .log(LogLevel::Warning, "Second\nwith a continuation")
+            .unwrap();
+
+        let records: Vec<ParsedRecord> = read_log(&log_file)
+            .unwrap()
+            .collect::<std::io::Result<_>>()
+            .unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].0, LogLevel::Info);
+        assert!(records[0].1.is_some());
+        assert_eq!(records[0].2, "First");
+        assert_eq!(records[1].0, LogLevel::Warning);
+        assert_eq!(records[1].2, "Second");
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+}
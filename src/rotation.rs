@@ -0,0 +1,286 @@
+// -------------------------------------------------------------------------------------------------
+//
+//  Implementation of a general purpose logger.
+//
+//  Copyright (c) 2025 by Dr. Panos Asproulis (p.asproulis@icloud.com).
+//  All Rights Reserved.
+//
+// -------------------------------------------------------------------------------------------------
+
+//! Implementation of the rotation of the log files.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::timestamp::{civil_from_days, days_from_civil};
+
+///
+/// The period after which the logging switches to a new file, whose name
+/// carries the period, e.g. `app-2025-01-02.log` for daily rotation.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum RotationPeriod {
+    ///
+    /// A new file every hour, e.g. `app-2025-01-02-13.log`.
+    ///
+    Hourly,
+    ///
+    /// A new file every day, e.g. `app-2025-01-02.log`.
+    ///
+    Daily,
+    ///
+    /// A new file every ISO 8601 week, e.g. `app-2025-W01.log`.
+    ///
+    Weekly,
+    ///
+    /// A new file every month, e.g. `app-2025-01.log`.
+    ///
+    Monthly,
+}
+
+//
+// Implementation of the `RotationPeriod` enum.
+//
+impl RotationPeriod {
+    ///
+    /// Get the label of the period containing a time, in UTC.
+    ///
+    /// - Arguments:
+    ///   - `time`: The time to label.
+    ///
+    /// - Returns:
+    ///   - The label of the period, e.g. `2025-W01` for weekly rotation.
+    ///
+    pub fn label(&self, time: SystemTime) -> String {
+        let seconds = time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let days = (seconds / 86_400) as i64;
+        let (year, month, day) = civil_from_days(days);
+
+        match self {
+            RotationPeriod::Hourly => format!(
+                "{:04}-{:02}-{:02}-{:02}",
+                year,
+                month,
+                day,
+                (seconds % 86_400) / 3_600
+            ),
+            RotationPeriod::Daily => format!("{:04}-{:02}-{:02}", year, month, day),
+            RotationPeriod::Weekly => {
+                //
+                // The ISO week belongs to the year of its Thursday.
+                //
+                let weekday = (days + 3).rem_euclid(7);
+                let thursday = days - weekday + 3;
+                let (iso_year, _, _) = civil_from_days(thursday);
+                let week = (thursday - days_from_civil(iso_year, 1, 1)) / 7 + 1;
+
+                format!("{:04}-W{:02}", iso_year, week)
+            }
+            RotationPeriod::Monthly => format!("{:04}-{:02}", year, month),
+        }
+    }
+}
+
+///
+/// Get the path of the log file of a rotation period, inserting the label
+/// of the period before the extension, e.g. `app-2025-01-02.log`.
+///
+/// - Arguments:
+///   - `path`: The configured path of the log file.
+///   - `label`: The label of the period.
+///
+/// - Returns:
+///   - The path of the log file of the period.
+///
+pub(crate) fn period_path(path: &Path, label: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+
+    match path.extension() {
+        Some(extension) => path.with_file_name(format!(
+            "{}-{}.{}",
+            stem,
+            label,
+            extension.to_string_lossy()
+        )),
+        None => path.with_file_name(format!("{}-{}", stem, label)),
+    }
+}
+
+///
+/// Get the path of a numbered backup of a log file, e.g. `app.log.3`.
+///
+/// - Arguments:
+///   - `path`: The path of the active log file.
+///   - `number`: The number of the backup, starting from 1 for the newest.
+///
+/// - Returns:
+///   - The path of the backup.
+///
+pub(crate) fn backup_path(path: &Path, number: usize) -> PathBuf {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(format!(".{}", number));
+    PathBuf::from(backup)
+}
+
+///
+/// Remove a file, ignoring the error if it does not exist.
+///
+/// - Arguments:
+///   - `path`: The path of the file to remove.
+///
+/// - Returns:
+///   - True if the file existed and was removed, or the error which occurred.
+///
+fn remove_if_exists(path: &Path) -> std::io::Result<bool> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(error) => Err(error),
+    }
+}
+
+///
+/// Move the active log file to the first numbered backup, shifting the
+/// existing backups by one. The oldest backup, along with any backups
+/// beyond `max_backups` left behind by a previous run, is deleted first,
+/// then `.k` is renamed to `.k+1` from the highest number to the lowest,
+/// so that no rename ever overwrites a backup which is still needed.
+/// With `max_backups` equal to zero the active log file is just deleted.
+///
+/// - Arguments:
+///   - `path`: The path of the active log file.
+///   - `max_backups`: The maximum number of backups to keep.
+///
+/// - Returns:
+///   - A result indicating success or failure.
+///
+pub(crate) fn rotate_backups(path: &Path, max_backups: usize) -> std::io::Result<()> {
+    if max_backups == 0 {
+        remove_if_exists(path)?;
+        return Ok(());
+    }
+    //
+    // Prune the oldest backup and any surplus backups.
+    //
+    let mut number = max_backups;
+
+    while remove_if_exists(&backup_path(path, number))? {
+        number += 1;
+    }
+    //
+    // Shift the remaining backups and move the active file to `.1`.
+    //
+    for number in (1..max_backups).rev() {
+        let backup = backup_path(path, number);
+
+        if backup.exists() {
+            std::fs::rename(&backup, backup_path(path, number + 1))?;
+        }
+    }
+
+    std::fs::rename(path, backup_path(path, 1))
+}
+
+// -------------------------------------------------------------------------------------------------
+//
+// Unit Tests.
+//
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+    use std::fs;
+
+    //
+    // Helper function to generate unique log filenames for tests
+    //
+    fn get_unique_log_filename() -> String {
+        let mut rng = rand::rng();
+        format!("test_log_{}.log", rng.random::<u64>())
+    }
+
+    #[test]
+    fn test_backup_path() {
+        assert_eq!(
+            backup_path(Path::new("logs/app.log"), 3),
+            PathBuf::from("logs/app.log.3")
+        );
+    }
+
+    #[test]
+    fn test_rotation_period_label() {
+        let time = |days: i64, hour: u64| {
+            SystemTime::UNIX_EPOCH
+                + std::time::Duration::from_secs(days as u64 * 86_400 + hour * 3_600)
+        };
+        let jan_2 = days_from_civil(2025, 1, 2);
+
+        assert_eq!(
+            RotationPeriod::Hourly.label(time(jan_2, 13)),
+            "2025-01-02-13"
+        );
+        assert_eq!(RotationPeriod::Daily.label(time(jan_2, 13)), "2025-01-02");
+        assert_eq!(RotationPeriod::Monthly.label(time(jan_2, 13)), "2025-01");
+        assert_eq!(RotationPeriod::Weekly.label(time(jan_2, 13)), "2025-W01");
+        //
+        // 2024-12-30 is a Monday in the first ISO week of 2025, while
+        // 2021-01-03 is a Sunday in the last ISO week of 2020.
+        //
+        assert_eq!(
+            RotationPeriod::Weekly.label(time(days_from_civil(2024, 12, 30), 0)),
+            "2025-W01"
+        );
+        assert_eq!(
+            RotationPeriod::Weekly.label(time(days_from_civil(2021, 1, 3), 0)),
+            "2020-W53"
+        );
+    }
+
+    #[test]
+    fn test_period_path() {
+        assert_eq!(
+            period_path(Path::new("logs/app.log"), "2025-W01"),
+            PathBuf::from("logs/app-2025-W01.log")
+        );
+        assert_eq!(
+            period_path(Path::new("app"), "2025-01"),
+            PathBuf::from("app-2025-01")
+        );
+    }
+
+    #[test]
+    fn test_rotate_backups_prunes_stale_backups() {
+        let log_file = get_unique_log_filename();
+        let path = Path::new(&log_file);
+        //
+        // Backups left behind by a previous run with more backups.
+        //
+        for number in 1..=4 {
+            fs::write(backup_path(path, number), format!("old {}", number)).unwrap();
+        }
+        fs::write(path, "active").unwrap();
+
+        rotate_backups(path, 2).unwrap();
+
+        assert!(!path.exists());
+        assert_eq!(fs::read_to_string(backup_path(path, 1)).unwrap(), "active");
+        assert_eq!(fs::read_to_string(backup_path(path, 2)).unwrap(), "old 1");
+        assert!(!backup_path(path, 3).exists());
+        assert!(!backup_path(path, 4).exists());
+        //
+        // Clean up.
+        //
+        fs::remove_file(backup_path(path, 1)).unwrap();
+        fs::remove_file(backup_path(path, 2)).unwrap();
+    }
+}
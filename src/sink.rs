@@ -0,0 +1,545 @@
+// -------------------------------------------------------------------------------------------------
+//
+//  Implementation of a general purpose logger.
+//
+//  Copyright (c) 2025 by Dr. Panos Asproulis (p.asproulis@icloud.com).
+//  All Rights Reserved.
+//
+// -------------------------------------------------------------------------------------------------
+
+//! Implementation of the sinks, i.e. the writers which forward the logged
+//! lines to destinations other than files. A sink is used as the log
+//! destination through `Logger::from_writer`.
+
+use std::collections::VecDeque;
+use std::io::prelude::*;
+use std::net::{TcpStream, ToSocketAddrs};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+///
+/// A sink which streams the logged lines to a Unix domain socket, such as
+/// the socket of a local log collector. The connection is established on
+/// the first write and re-established once if a write fails, e.g. after
+/// the collector was restarted.
+///
+#[cfg(unix)]
+#[derive(Debug)]
+pub struct UnixSocketSink {
+    ///
+    /// The path of the socket.
+    ///
+    path: PathBuf,
+    ///
+    /// The connection to the socket, if established.
+    ///
+    stream: Option<UnixStream>,
+}
+
+//
+// Implementation of the `UnixSocketSink` struct.
+//
+#[cfg(unix)]
+impl UnixSocketSink {
+    ///
+    /// Create a new `UnixSocketSink` which connects on the first write.
+    ///
+    /// - Arguments:
+    ///   - `path`: The path of the socket.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `UnixSocketSink` object.
+    ///
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            stream: None,
+        }
+    }
+
+    ///
+    /// Create a new `UnixSocketSink` and connect to the socket immediately.
+    ///
+    /// - Arguments:
+    ///   - `path`: The path of the socket.
+    ///
+    /// - Returns:
+    ///   - The connected `UnixSocketSink` object or the error which occurred.
+    ///
+    pub fn connect(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let mut sink = Self::new(path);
+        sink.stream = Some(UnixStream::connect(&sink.path)?);
+        Ok(sink)
+    }
+
+    ///
+    /// Write a buffer to the socket, connecting first if necessary.
+    ///
+    /// - Arguments:
+    ///   - `buf`: The bytes to write.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    fn send(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        let stream = match &mut self.stream {
+            Some(stream) => stream,
+            None => self.stream.insert(UnixStream::connect(&self.path)?),
+        };
+
+        let result = stream.write_all(buf);
+
+        if result.is_err() {
+            self.stream = None;
+        }
+
+        result
+    }
+}
+
+//
+// Implementation of the `Write` trait for `UnixSocketSink`.
+//
+#[cfg(unix)]
+impl Write for UnixSocketSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        //
+        // A failed write drops the connection, so retry once over a new one.
+        //
+        if self.send(buf).is_err() {
+            self.send(buf)?;
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match &mut self.stream {
+            Some(stream) => stream.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+///
+/// A sink which streams the logged lines to a remote collector over TCP.
+/// The lines are queued and sent while the connection is up. When the
+/// collector is unreachable they stay queued and the connection is retried
+/// at most once per retry interval, so `log` never blocks for longer than
+/// the timeout. When the queue is full the oldest line is dropped.
+///
+#[derive(Debug)]
+pub struct TcpSink {
+    ///
+    /// The address of the collector as `host:port`.
+    ///
+    address: String,
+    ///
+    /// The connection to the collector, if established.
+    ///
+    stream: Option<TcpStream>,
+    ///
+    /// The lines waiting to be sent.
+    ///
+    queue: VecDeque<Vec<u8>>,
+    ///
+    /// The maximum number of queued lines.
+    ///
+    capacity: usize,
+    ///
+    /// The timeout for connecting and writing.
+    ///
+    timeout: Duration,
+    ///
+    /// The minimum time between two connection attempts.
+    ///
+    retry_interval: Duration,
+    ///
+    /// The time of the last failed connection attempt.
+    ///
+    last_attempt: Option<Instant>,
+    ///
+    /// The number of lines dropped because the queue was full.
+    ///
+    dropped: Arc<AtomicU64>,
+}
+
+//
+// Implementation of the `TcpSink` struct.
+//
+impl TcpSink {
+    ///
+    /// Create a new `TcpSink` which connects on the first write, with a
+    /// queue of 1024 lines, a timeout of one second and a retry interval
+    /// of one second.
+    ///
+    /// - Arguments:
+    ///   - `address`: The address of the collector as `host:port`.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `TcpSink` object.
+    ///
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+            stream: None,
+            queue: VecDeque::new(),
+            capacity: 1024,
+            timeout: Duration::from_secs(1),
+            retry_interval: Duration::from_secs(1),
+            last_attempt: None,
+            dropped: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    ///
+    /// Set the maximum number of lines queued while the collector is
+    /// unreachable.
+    ///
+    /// - Arguments:
+    ///   - `capacity`: The maximum number of queued lines, at least 1.
+    ///
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+    }
+
+    ///
+    /// Set the timeout for connecting to the collector and writing to it.
+    ///
+    /// - Arguments:
+    ///   - `timeout`: The timeout, which must not be zero.
+    ///
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        if !timeout.is_zero() {
+            self.timeout = timeout;
+        }
+    }
+
+    ///
+    /// Set the minimum time between two connection attempts.
+    ///
+    /// - Arguments:
+    ///   - `retry_interval`: The retry interval.
+    ///
+    pub fn set_retry_interval(&mut self, retry_interval: Duration) {
+        self.retry_interval = retry_interval;
+    }
+
+    ///
+    /// Get the counter of the lines dropped because the queue was full.
+    /// The counter is shared, so it may be read after the sink has been
+    /// moved into a `Logger`.
+    ///
+    /// - Returns:
+    ///   - The shared counter of dropped lines.
+    ///
+    pub fn drop_counter(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.dropped)
+    }
+
+    ///
+    /// Get the number of lines waiting to be sent.
+    ///
+    /// - Returns:
+    ///   - The number of queued lines.
+    ///
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    ///
+    /// Connect to the collector unless the last attempt was too recent.
+    ///
+    /// - Returns:
+    ///   - True if the sink is connected.
+    ///
+    fn connect(&mut self) -> bool {
+        if self.stream.is_some() {
+            return true;
+        }
+
+        if let Some(last_attempt) = self.last_attempt {
+            if last_attempt.elapsed() < self.retry_interval {
+                return false;
+            }
+        }
+
+        let stream = self.address.to_socket_addrs().ok().and_then(|addresses| {
+            addresses
+                .filter_map(|address| TcpStream::connect_timeout(&address, self.timeout).ok())
+                .next()
+        });
+
+        match stream {
+            Some(stream) => {
+                let _ = stream.set_write_timeout(Some(self.timeout));
+                self.stream = Some(stream);
+                self.last_attempt = None;
+                true
+            }
+            None => {
+                self.last_attempt = Some(Instant::now());
+                false
+            }
+        }
+    }
+
+    ///
+    /// Send the queued lines in order while the connection is up. A line
+    /// stays queued until it has been written completely.
+    ///
+    fn drain(&mut self) {
+        while !self.queue.is_empty() && self.connect() {
+            let Some(stream) = &mut self.stream else {
+                break;
+            };
+
+            if stream.write_all(&self.queue[0]).is_err() {
+                self.stream = None;
+                self.last_attempt = Some(Instant::now());
+                break;
+            }
+
+            self.queue.pop_front();
+        }
+    }
+}
+
+//
+// Implementation of the `Write` trait for `TcpSink`.
+//
+impl Write for TcpSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.queue.len() >= self.capacity {
+            self.queue.pop_front();
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+
+        self.queue.push_back(buf.to_vec());
+        self.drain();
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        //
+        // Lines which could not be sent stay queued for a later attempt.
+        //
+        self.drain();
+
+        match &mut self.stream {
+            Some(stream) => {
+                let _ = stream.flush();
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+///
+/// A sink which keeps the most recent logged lines in memory, e.g. for a
+/// "recent events" panel or a crash dump. Once the capacity is reached the
+/// oldest line is overwritten. The clones of the sink share the lines, so
+/// a clone may be kept to take snapshots after the sink has been moved
+/// into a `Logger`.
+///
+#[derive(Debug, Clone)]
+pub struct RingBufferSink {
+    ///
+    /// The most recent lines, oldest first, without their line endings.
+    ///
+    lines: Arc<Mutex<VecDeque<String>>>,
+    ///
+    /// The maximum number of lines kept.
+    ///
+    capacity: usize,
+}
+
+//
+// Implementation of the `RingBufferSink` struct.
+//
+impl RingBufferSink {
+    ///
+    /// Create a new `RingBufferSink`.
+    ///
+    /// - Arguments:
+    ///   - `capacity`: The maximum number of lines kept, at least 1.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `RingBufferSink` object.
+    ///
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+
+        Self {
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    ///
+    /// Get a copy of the lines currently kept.
+    ///
+    /// - Returns:
+    ///   - The kept lines, oldest first, without their line endings.
+    ///
+    pub fn snapshot(&self) -> Vec<String> {
+        self.lines
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .cloned()
+            .collect()
+    }
+}
+
+//
+// Implementation of the `Write` trait for `RingBufferSink`.
+//
+impl Write for RingBufferSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        let mut lines = self.lines.lock().unwrap_or_else(PoisonError::into_inner);
+        //
+        // A buffered batch may hold several lines.
+        //
+        for line in text.lines() {
+            if lines.len() == self.capacity {
+                lines.pop_front();
+            }
+
+            lines.push_back(line.to_string());
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//
+// Unit Tests.
+//
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::levels::LogLevel;
+    use crate::logger::Logger;
+    use rand::prelude::*;
+    use std::io::BufReader;
+
+    //
+    // Helper function to generate unique socket paths for tests
+    //
+    #[cfg(unix)]
+    fn get_unique_socket_path() -> PathBuf {
+        let mut rng = rand::rng();
+        std::env::temp_dir().join(format!("test_log_{}.sock", rng.random::<u64>()))
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_socket_sink() {
+        let path = get_unique_socket_path();
+        let listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+        let logger = Logger::from_writer(UnixSocketSink::new(&path), false);
+
+        logger.log(LogLevel::Info, "Forwarded").unwrap();
+
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "[INFO] Forwarded\n");
+        //
+        // The collector drops the connection and the sink reconnects.
+        //
+        drop(reader);
+        logger.log(LogLevel::Info, "Reconnected").unwrap();
+
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "[INFO] Reconnected\n");
+        //
+        // Clean up.
+        //
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_socket_sink_connect_refused() {
+        let path = get_unique_socket_path();
+        assert!(UnixSocketSink::connect(&path).is_err());
+    }
+
+    #[test]
+    fn test_tcp_sink() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let logger = Logger::from_writer(TcpSink::new(address), false);
+
+        logger.log(LogLevel::Info, "First").unwrap();
+        logger.log(LogLevel::Warning, "Second").unwrap();
+
+        let (stream, _) = listener.accept().unwrap();
+        let mut lines = BufReader::new(stream).lines();
+        assert_eq!(lines.next().unwrap().unwrap(), "[INFO] First");
+        assert_eq!(lines.next().unwrap().unwrap(), "[WARNING] Second");
+    }
+
+    #[test]
+    fn test_tcp_sink_connection_refused() {
+        //
+        // Nothing listens on the port once the listener is dropped.
+        //
+        let address = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .to_string();
+        let mut sink = TcpSink::new(address);
+        sink.set_capacity(2);
+        let dropped = sink.drop_counter();
+
+        for number in 0..5 {
+            sink.write_all(format!("Line {}\n", number).as_bytes())
+                .unwrap();
+        }
+        sink.flush().unwrap();
+
+        assert_eq!(sink.queued(), 2);
+        assert_eq!(dropped.load(Ordering::Relaxed), 3);
+        assert_eq!(sink.queue[0], b"Line 3\n");
+    }
+
+    #[test]
+    fn test_ring_buffer_sink() {
+        let sink = RingBufferSink::new(3);
+        let logger = Logger::from_writer(sink.clone(), false);
+
+        for number in 1..=5 {
+            logger
+                .log(LogLevel::Info, &format!("Event {}", number))
+                .unwrap();
+        }
+
+        assert_eq!(
+            sink.snapshot(),
+            vec!["[INFO] Event 3", "[INFO] Event 4", "[INFO] Event 5"]
+        );
+    }
+}
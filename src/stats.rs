@@ -0,0 +1,273 @@
+// -------------------------------------------------------------------------------------------------
+//
+//  Implementation of a general purpose logger.
+//
+//  Copyright (c) 2025 by Dr. Panos Asproulis (p.asproulis@icloud.com).
+//  All Rights Reserved.
+//
+// -------------------------------------------------------------------------------------------------
+
+//! Implementation of the logging statistics.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+use crate::levels::LogLevel;
+
+///
+/// The number of messages logged at each level.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LevelCounts {
+    ///
+    /// The number of `Info` messages.
+    ///
+    pub info: u64,
+    ///
+    /// The number of `Debug` messages.
+    ///
+    pub debug: u64,
+    ///
+    /// The number of `Warning` messages.
+    ///
+    pub warning: u64,
+    ///
+    /// The number of `Error` messages.
+    ///
+    pub error: u64,
+}
+
+//
+// Implementation of the `LevelCounts` struct.
+//
+impl LevelCounts {
+    ///
+    /// The total number of messages logged at all levels.
+    ///
+    /// - Returns:
+    ///   - The sum of all the per-level counts.
+    ///
+    pub fn total(&self) -> u64 {
+        self.info + self.debug + self.warning + self.error
+    }
+}
+
+///
+/// A snapshot of the statistics of a logger, e.g. for a health endpoint.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LoggerStats {
+    ///
+    /// The total number of messages logged at all levels.
+    ///
+    pub total_records: u64,
+    ///
+    /// The total number of bytes written to the log destination.
+    ///
+    pub total_bytes: u64,
+    ///
+    /// The number of messages logged at each level.
+    ///
+    pub counts: LevelCounts,
+    ///
+    /// The time of the last written record, if any.
+    ///
+    pub last_record_time: Option<SystemTime>,
+    ///
+    /// The number of records discarded because they could not be written.
+    ///
+    pub dropped_records: u64,
+}
+
+///
+/// Thread-safe counters of the bytes and the time of the written records.
+///
+#[derive(Debug, Default)]
+pub(crate) struct OutputCounters {
+    ///
+    /// The number of bytes written to the log destination.
+    ///
+    bytes: AtomicU64,
+    ///
+    /// The time of the last written record in nanoseconds since the Unix
+    /// epoch, or zero if no record was written.
+    ///
+    last_record_nanos: AtomicU64,
+    ///
+    /// The number of records discarded because they could not be written.
+    ///
+    dropped: AtomicU64,
+}
+
+//
+// Implementation of the `OutputCounters` struct.
+//
+impl OutputCounters {
+    ///
+    /// Add to the number of bytes written.
+    ///
+    /// - Arguments:
+    ///   - `bytes`: The number of bytes written.
+    ///
+    pub(crate) fn add_bytes(&self, bytes: usize) {
+        self.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    ///
+    /// Count a record discarded because it could not be written.
+    ///
+    pub(crate) fn add_dropped(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    ///
+    /// Record the time of a written record.
+    ///
+    /// - Arguments:
+    ///   - `time`: The time of the record.
+    ///
+    pub(crate) fn set_last_record_time(&self, time: SystemTime) {
+        let nanos = time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64;
+        self.last_record_nanos
+            .store(nanos.max(1), Ordering::Relaxed);
+    }
+
+    ///
+    /// Take a snapshot of the statistics.
+    ///
+    /// - Arguments:
+    ///   - `counts`: The current per-level counts.
+    ///
+    /// - Returns:
+    ///   - The current statistics.
+    ///
+    pub(crate) fn snapshot(&self, counts: LevelCounts) -> LoggerStats {
+        let nanos = self.last_record_nanos.load(Ordering::Relaxed);
+
+        LoggerStats {
+            total_records: counts.total(),
+            total_bytes: self.bytes.load(Ordering::Relaxed),
+            counts,
+            last_record_time: (nanos != 0)
+                .then(|| SystemTime::UNIX_EPOCH + Duration::from_nanos(nanos)),
+            dropped_records: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+///
+/// Thread-safe counters of the messages logged at each level.
+///
+#[derive(Debug, Default)]
+pub(crate) struct LevelCounters {
+    ///
+    /// The `Info` counter.
+    ///
+    info: AtomicU64,
+    ///
+    /// The `Debug` counter.
+    ///
+    debug: AtomicU64,
+    ///
+    /// The `Warning` counter.
+    ///
+    warning: AtomicU64,
+    ///
+    /// The `Error` counter.
+    ///
+    error: AtomicU64,
+}
+
+//
+// Implementation of the `LevelCounters` struct.
+//
+impl LevelCounters {
+    ///
+    /// Increment the counter of the specified level.
+    ///
+    /// - Arguments:
+    ///   - `level`: The level of the logged message.
+    ///
+    /// - Returns:
+    ///   - The value of the counter before the increment.
+    ///
+    pub(crate) fn increment(&self, level: LogLevel) -> u64 {
+        let counter = match level {
+            LogLevel::Info => &self.info,
+            LogLevel::Debug => &self.debug,
+            LogLevel::Warning => &self.warning,
+            LogLevel::Error => &self.error,
+        };
+
+        counter.fetch_add(1, Ordering::Relaxed)
+    }
+
+    ///
+    /// Take a snapshot of the counters.
+    ///
+    /// - Returns:
+    ///   - The current per-level counts.
+    ///
+    pub(crate) fn snapshot(&self) -> LevelCounts {
+        LevelCounts {
+            info: self.info.load(Ordering::Relaxed),
+            debug: self.debug.load(Ordering::Relaxed),
+            warning: self.warning.load(Ordering::Relaxed),
+            error: self.error.load(Ordering::Relaxed),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//
+// Unit Tests.
+//
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_counters() {
+        let counters = LevelCounters::default();
+
+        assert_eq!(counters.increment(LogLevel::Info), 0);
+        assert_eq!(counters.increment(LogLevel::Error), 0);
+        assert_eq!(counters.increment(LogLevel::Error), 1);
+
+        let counts = counters.snapshot();
+        assert_eq!(counts.info, 1);
+        assert_eq!(counts.debug, 0);
+        assert_eq!(counts.warning, 0);
+        assert_eq!(counts.error, 2);
+        assert_eq!(counts.total(), 3);
+    }
+
+    #[test]
+    fn test_output_counters() {
+        let counters = OutputCounters::default();
+        assert_eq!(
+            counters.snapshot(LevelCounts::default()).last_record_time,
+            None
+        );
+
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        counters.add_bytes(10);
+        counters.add_bytes(5);
+        counters.set_last_record_time(time);
+        counters.add_dropped();
+
+        let stats = counters.snapshot(LevelCounts {
+            info: 2,
+            ..LevelCounts::default()
+        });
+        assert_eq!(stats.total_records, 2);
+        assert_eq!(stats.total_bytes, 15);
+        assert_eq!(stats.last_record_time, Some(time));
+        assert_eq!(stats.dropped_records, 1);
+    }
+}
@@ -0,0 +1,103 @@
+// -------------------------------------------------------------------------------------------------
+//
+//  Implementation of a general purpose logger.
+//
+//  Copyright (c) 2025 by Dr. Panos Asproulis (p.asproulis@icloud.com).
+//  All Rights Reserved.
+//
+// -------------------------------------------------------------------------------------------------
+
+//! Implementation of the helpers for testing code which logs.
+//!
+//! They are available with the `test-util` feature enabled.
+
+use std::sync::PoisonError;
+
+use tempfile::TempPath;
+
+use crate::logger::Logger;
+
+//
+// Implementation of the `Logger` test helpers.
+//
+impl Logger {
+    ///
+    /// Create a new `Logger` backed by a uniquely named temporary file,
+    /// which is deleted when the returned `TempPath` is dropped, even if
+    /// the test panics. Errors do not terminate the application.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `Logger` object and the path of its log
+    ///     file.
+    ///
+    /// - Panics:
+    ///   - If the temporary file cannot be created.
+    ///
+    pub fn temp() -> (Logger, TempPath) {
+        let path = tempfile::Builder::new()
+            .prefix("test_log_")
+            .suffix(".log")
+            .tempfile()
+            .expect("Logger: I cannot create the temporary log file.")
+            .into_temp_path();
+        let logger = Logger::new(&path.to_string_lossy(), false);
+
+        (logger, path)
+    }
+
+    ///
+    /// Assert that no `Warning` or `Error` records were logged by this
+    /// `Logger` or its clones, turning the logger into a lightweight test
+    /// assertion.
+    ///
+    /// - Panics:
+    ///   - If any `Warning` or `Error` records were logged, listing them.
+    ///
+    pub fn assert_clean(&self) {
+        let issues = self.issues.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if !issues.is_empty() {
+            panic!(
+                "Logger: {} warning/error record(s) were logged:\n{}",
+                issues.len(),
+                issues.join("\n")
+            );
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//
+// Unit Tests.
+//
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::levels::LogLevel;
+    use crate::logger::Logger;
+
+    #[test]
+    fn test_assert_clean_passes() {
+        let (logger, _path) = Logger::temp();
+
+        logger.log(LogLevel::Info, "All good").unwrap();
+        logger.log(LogLevel::Debug, "Still good").unwrap();
+
+        logger.assert_clean();
+    }
+
+    #[test]
+    #[should_panic(expected = "[WARNING] Disk almost full")]
+    fn test_assert_clean_fails_on_warning() {
+        let (logger, _path) = Logger::temp();
+
+        logger.log(LogLevel::Info, "All good").unwrap();
+        logger
+            .clone()
+            .log(LogLevel::Warning, "Disk almost full")
+            .unwrap();
+
+        logger.assert_clean();
+    }
+}
@@ -0,0 +1,332 @@
+// -------------------------------------------------------------------------------------------------
+//
+//  Implementation of a general purpose logger.
+//
+//  Copyright (c) 2025 by Dr. Panos Asproulis (p.asproulis@icloud.com).
+//  All Rights Reserved.
+//
+// -------------------------------------------------------------------------------------------------
+
+//! Implementation of the timestamp formats.
+
+use std::time::{Duration, SystemTime};
+
+///
+/// The format of the timestamp prefixed to each logged message.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum TimestampFormat {
+    ///
+    /// No timestamp.
+    ///
+    #[default]
+    None,
+    ///
+    /// Wall-clock UTC time in RFC 3339 format, e.g. `2025-01-02T13:45:01Z`.
+    ///
+    Rfc3339,
+    ///
+    /// Wall-clock UTC time in RFC 3339 format with millisecond precision,
+    /// e.g. `2025-01-02T13:45:01.123Z`.
+    ///
+    Rfc3339Millis,
+    ///
+    /// Wall-clock UTC time in RFC 3339 format with microsecond precision,
+    /// e.g. `2025-01-02T13:45:01.123456Z`.
+    ///
+    Rfc3339Micros,
+    ///
+    /// Monotonic seconds elapsed since the creation of the logger, e.g.
+    /// `[+12.345s]`. Unaffected by adjustments of the system clock.
+    ///
+    UptimeSeconds,
+}
+
+//
+// Implementation of the `TimestampFormat` enum.
+//
+impl TimestampFormat {
+    ///
+    /// Format a wall-clock time according to this format.
+    ///
+    /// - Arguments:
+    ///   - `time`: The time to format.
+    ///
+    /// - Returns:
+    ///   - The formatted time, or `None` if this is not a wall-clock format.
+    ///
+    pub fn format_time(&self, time: SystemTime) -> Option<String> {
+        match self {
+            TimestampFormat::Rfc3339 => Some(format_rfc3339_with_precision(time, 0)),
+            TimestampFormat::Rfc3339Millis => Some(format_rfc3339_with_precision(time, 3)),
+            TimestampFormat::Rfc3339Micros => Some(format_rfc3339_with_precision(time, 6)),
+            TimestampFormat::None | TimestampFormat::UptimeSeconds => None,
+        }
+    }
+
+    ///
+    /// Format a wall-clock time according to this format in a specific
+    /// timezone, with the UTC offset in effect at that instant, so that
+    /// daylight saving time transitions are rendered correctly.
+    ///
+    /// - Arguments:
+    ///   - `time`: The time to format.
+    ///   - `timezone`: The timezone in which to render the time.
+    ///
+    /// - Returns:
+    ///   - The formatted time, e.g. `2025-01-02T08:45:01-05:00`, or `None`
+    ///     if this is not a wall-clock format.
+    ///
+    #[cfg(feature = "timezone")]
+    pub fn format_time_in(&self, time: SystemTime, timezone: chrono_tz::Tz) -> Option<String> {
+        use chrono::SecondsFormat;
+
+        let precision = match self {
+            TimestampFormat::Rfc3339 => SecondsFormat::Secs,
+            TimestampFormat::Rfc3339Millis => SecondsFormat::Millis,
+            TimestampFormat::Rfc3339Micros => SecondsFormat::Micros,
+            TimestampFormat::None | TimestampFormat::UptimeSeconds => return None,
+        };
+
+        Some(
+            chrono::DateTime::<chrono::Utc>::from(time)
+                .with_timezone(&timezone)
+                .to_rfc3339_opts(precision, false),
+        )
+    }
+}
+
+///
+/// Format a time in RFC 3339 format using the UTC timezone.
+///
+/// - Arguments:
+///   - `time`: The time to format.
+///
+/// - Returns:
+///   - The formatted time, e.g. `2025-01-02T13:45:01Z`.
+///
+pub fn format_rfc3339(time: SystemTime) -> String {
+    format_rfc3339_with_precision(time, 0)
+}
+
+///
+/// Format a time in RFC 3339 format using the UTC timezone, with the
+/// specified number of fractional-second digits.
+///
+/// - Arguments:
+///   - `time`: The time to format.
+///   - `digits`: The number of fractional-second digits, from 0 to 9.
+///
+/// - Returns:
+///   - The formatted time, e.g. `2025-01-02T13:45:01.123456Z` for 6 digits.
+///
+pub fn format_rfc3339_with_precision(time: SystemTime, digits: u32) -> String {
+    let since_epoch = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    let seconds = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    let seconds_of_day = seconds % 86_400;
+
+    let fraction = match digits.min(9) {
+        0 => String::new(),
+        digits => format!(
+            ".{:0width$}",
+            since_epoch.subsec_nanos() / 10_u32.pow(9 - digits),
+            width = digits as usize
+        ),
+    };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}Z",
+        year,
+        month,
+        day,
+        seconds_of_day / 3_600,
+        (seconds_of_day % 3_600) / 60,
+        seconds_of_day % 60,
+        fraction
+    )
+}
+
+///
+/// Format the time elapsed since the creation of a logger.
+///
+/// - Arguments:
+///   - `uptime`: The elapsed time.
+///
+/// - Returns:
+///   - The formatted uptime, e.g. `[+12.345s]`.
+///
+pub fn format_uptime(uptime: Duration) -> String {
+    format!("[+{:.3}s]", uptime.as_secs_f64())
+}
+
+///
+/// Format the time elapsed since the previous record, in milliseconds
+/// below one second and in seconds otherwise.
+///
+/// - Arguments:
+///   - `delta`: The elapsed time.
+///
+/// - Returns:
+///   - The formatted delta, e.g. `[+4.2ms]` or `[+1.250s]`.
+///
+pub fn format_delta(delta: Duration) -> String {
+    if delta < Duration::from_secs(1) {
+        format!("[+{:.1}ms]", delta.as_secs_f64() * 1_000.0)
+    } else {
+        format!("[+{:.3}s]", delta.as_secs_f64())
+    }
+}
+
+///
+/// Convert a number of days since the Unix epoch to a civil date in the
+/// proleptic Gregorian calendar.
+///
+/// - Arguments:
+///   - `days`: The number of days since 1970-01-01.
+///
+/// - Returns:
+///   - The `(year, month, day)` of the date.
+///
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+///
+/// Convert a civil date in the proleptic Gregorian calendar to a number of
+/// days since the Unix epoch.
+///
+/// - Arguments:
+///   - `year`: The year of the date.
+///   - `month`: The month of the date, from 1 to 12.
+///   - `day`: The day of the month, from 1 to 31.
+///
+/// - Returns:
+///   - The number of days since 1970-01-01.
+///
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = if month > 2 { month - 3 } else { month + 9 } as i64;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146_097 + doe - 719_468
+}
+
+// -------------------------------------------------------------------------------------------------
+//
+// Unit Tests.
+//
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_rfc3339() {
+        assert_eq!(
+            format_rfc3339(SystemTime::UNIX_EPOCH),
+            "1970-01-01T00:00:00Z"
+        );
+        assert_eq!(
+            format_rfc3339(SystemTime::UNIX_EPOCH + Duration::from_secs(1_735_825_501)),
+            "2025-01-02T13:45:01Z"
+        );
+        assert_eq!(
+            format_rfc3339(SystemTime::UNIX_EPOCH + Duration::from_secs(951_782_400)),
+            "2000-02-29T00:00:00Z"
+        );
+    }
+
+    #[test]
+    fn test_format_rfc3339_with_precision() {
+        let time = SystemTime::UNIX_EPOCH + Duration::new(1_735_825_501, 123_456_789);
+        assert_eq!(
+            format_rfc3339_with_precision(time, 0),
+            "2025-01-02T13:45:01Z"
+        );
+        assert_eq!(
+            format_rfc3339_with_precision(time, 3),
+            "2025-01-02T13:45:01.123Z"
+        );
+        assert_eq!(
+            format_rfc3339_with_precision(time, 6),
+            "2025-01-02T13:45:01.123456Z"
+        );
+        assert_eq!(
+            TimestampFormat::Rfc3339Micros.format_time(time).unwrap(),
+            "2025-01-02T13:45:01.123456Z"
+        );
+        assert!(TimestampFormat::UptimeSeconds.format_time(time).is_none());
+    }
+
+    #[test]
+    fn test_days_from_civil() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(civil_from_days(days_from_civil(2024, 2, 29)), (2024, 2, 29));
+    }
+
+    #[test]
+    fn test_format_uptime() {
+        assert_eq!(format_uptime(Duration::ZERO), "[+0.000s]");
+        assert_eq!(format_uptime(Duration::from_millis(12_345)), "[+12.345s]");
+    }
+
+    #[test]
+    fn test_format_delta() {
+        assert_eq!(format_delta(Duration::ZERO), "[+0.0ms]");
+        assert_eq!(format_delta(Duration::from_micros(4_200)), "[+4.2ms]");
+        assert_eq!(format_delta(Duration::from_millis(1_250)), "[+1.250s]");
+    }
+
+    #[cfg(feature = "timezone")]
+    #[test]
+    fn test_format_time_in_timezone() {
+        let winter = SystemTime::UNIX_EPOCH
+            + Duration::from_secs(days_from_civil(2025, 1, 2) as u64 * 86_400);
+        let summer = SystemTime::UNIX_EPOCH
+            + Duration::from_secs(days_from_civil(2025, 7, 2) as u64 * 86_400);
+        let format = TimestampFormat::Rfc3339;
+
+        assert_eq!(
+            format.format_time_in(winter, chrono_tz::America::New_York),
+            Some("2025-01-01T19:00:00-05:00".to_string())
+        );
+        assert_eq!(
+            format.format_time_in(winter, chrono_tz::Asia::Tokyo),
+            Some("2025-01-02T09:00:00+09:00".to_string())
+        );
+        //
+        // Daylight saving time is in effect in New York in July.
+        //
+        assert_eq!(
+            format.format_time_in(summer, chrono_tz::America::New_York),
+            Some("2025-07-01T20:00:00-04:00".to_string())
+        );
+        assert_eq!(
+            TimestampFormat::UptimeSeconds.format_time_in(winter, chrono_tz::UTC),
+            None
+        );
+    }
+}
@@ -0,0 +1,215 @@
+// -------------------------------------------------------------------------------------------------
+//
+//  Implementation of a general purpose logger.
+//
+//  Copyright (c) 2025 by Dr. Panos Asproulis (p.asproulis@icloud.com).
+//  All Rights Reserved.
+//
+// -------------------------------------------------------------------------------------------------
+
+//! Implementation of the `Write` adapter which streams bytes into a logger.
+
+use std::io::Write;
+
+use crate::levels::LogLevel;
+use crate::logger::Logger;
+
+///
+/// A `Write` adapter which logs each complete line written to it as a
+/// record of a specific level. Partial lines are held until their newline
+/// arrives and the remainder is logged when the adapter is dropped. The
+/// bytes are consumed once they are held, so an error logging a line is
+/// reported by the next call to `write` or `flush`, and a retry never logs
+/// a line twice.
+///
+#[derive(Debug)]
+pub struct LevelWriter<'a> {
+    ///
+    /// The logger used for logging the lines.
+    ///
+    logger: &'a Logger,
+    ///
+    /// The level of the logged records.
+    ///
+    level: LogLevel,
+    ///
+    /// The bytes of the incomplete line written so far.
+    ///
+    buffer: Vec<u8>,
+    ///
+    /// The error which occurred logging a line, reported by the next call.
+    ///
+    error: Option<std::io::Error>,
+}
+
+//
+// Implementation of the `LevelWriter` struct.
+//
+impl<'a> LevelWriter<'a> {
+    ///
+    /// Create a new `LevelWriter`.
+    ///
+    /// - Arguments:
+    ///   - `logger`: The logger used for logging the lines.
+    ///   - `level`: The level of the logged records.
+    ///
+    /// - Returns:
+    ///   - The newly constructed `LevelWriter` object.
+    ///
+    pub fn new(logger: &'a Logger, level: LogLevel) -> Self {
+        Self {
+            logger,
+            level,
+            buffer: Vec::new(),
+            error: None,
+        }
+    }
+
+    ///
+    /// Log a line as a record.
+    ///
+    /// - Arguments:
+    ///   - `line`: The bytes of the line, excluding the newline.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    fn log_line(&self, line: &[u8]) -> std::io::Result<()> {
+        let line = String::from_utf8_lossy(line);
+        let line = line.strip_suffix('\r').unwrap_or(&line);
+
+        self.logger
+            .log(self.level, line)
+            .map_err(std::io::Error::other)
+    }
+}
+
+//
+// Implementation of the `Write` trait for `LevelWriter`.
+//
+impl Write for LevelWriter<'_> {
+    ///
+    /// Write bytes to the adapter, logging every line they complete.
+    ///
+    /// - Arguments:
+    ///   - `buf`: The bytes to write.
+    ///
+    /// - Returns:
+    ///   - The number of bytes written, or the error which occurred logging
+    ///     a line during the previous call, in which case no bytes are
+    ///     written.
+    ///
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+
+        self.buffer.extend_from_slice(buf);
+
+        while let Some(end) = self.buffer.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+
+            if let Err(error) = self.log_line(&line[..end]) {
+                self.error = Some(error);
+                break;
+            }
+        }
+
+        Ok(buf.len())
+    }
+
+    ///
+    /// Flush the adapter. Incomplete lines are held until their newline
+    /// arrives, so this only reports the error which occurred logging a
+    /// line, if any.
+    ///
+    /// - Returns:
+    ///   - A result indicating success or failure.
+    ///
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+//
+// Implementation of the `Drop` trait for `LevelWriter`.
+//
+impl Drop for LevelWriter<'_> {
+    ///
+    /// Log the remaining incomplete line, if any.
+    ///
+    fn drop(&mut self) {
+        if !self.buffer.is_empty() {
+            let line = std::mem::take(&mut self.buffer);
+            let _ = self.log_line(&line);
+        }
+    }
+}
+
+//
+// Implementation of the `Logger` constructor of `LevelWriter`.
+//
+impl Logger {
+    ///
+    /// Get a `Write` adapter which logs each complete line written to it
+    /// as a record of the specified level, e.g.
+    /// `writeln!(logger.writer_for(LogLevel::Info), "x={}", x)`.
+    ///
+    /// - Arguments:
+    ///   - `level`: The level of the logged records.
+    ///
+    /// - Returns:
+    ///   - The `Write` adapter.
+    ///
+    pub fn writer_for(&self, level: LogLevel) -> LevelWriter<'_> {
+        LevelWriter::new(self, level)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//
+// Unit Tests.
+//
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+    use std::fs;
+
+    //
+    // Helper function to generate unique log filenames for tests
+    //
+    fn get_unique_log_filename() -> String {
+        let mut rng = rand::rng();
+        format!("test_log_{}.log", rng.random::<u64>())
+    }
+
+    #[test]
+    fn test_writer_for_logs_complete_lines() {
+        let log_file = get_unique_log_filename();
+        let logger = Logger::new(&log_file, false);
+
+        let mut writer = logger.writer_for(LogLevel::Info);
+        writeln!(writer, "x={}", 1).unwrap();
+        write!(writer, "y=").unwrap();
+        assert_eq!(fs::read_to_string(&log_file).unwrap(), "[INFO] x=1\n");
+
+        writeln!(writer, "{}", 2).unwrap();
+        write!(writer, "partial").unwrap();
+        drop(writer);
+
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "[INFO] x=1\n[INFO] y=2\n[INFO] partial\n"
+        );
+        //
+        // Clean up.
+        //
+        fs::remove_file(&log_file).unwrap();
+    }
+}